@@ -1,10 +1,60 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    /// Units of the physical objects seen so far, keyed by full name, or
+    /// `None` when the type could not be resolved.
+    static PHYSICAL_TYPES: RefCell<HashMap<String, Option<vhpi::PhysicalType>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Format physical values with the units of their type.
+///
+/// The units are resolved once per object, and `value` is converted
+/// directly rather than read again.
+fn format_value(obj: &vhpi::Handle, full_name: &str, value: &vhpi::Value) -> String {
+    let (raw, is_vec): (Vec<i64>, bool) = match value {
+        vhpi::Value::Physical(phys) => (vec![phys.to_i64()], false),
+        vhpi::Value::SmallPhysical(phys) => (vec![i64::from(*phys)], false),
+        vhpi::Value::PhysicalVec(values) => {
+            (values.iter().map(vhpi::Physical::to_i64).collect(), true)
+        }
+        vhpi::Value::SmallPhysicalVec(values) => {
+            (values.iter().map(|&phys| i64::from(phys)).collect(), true)
+        }
+        _ => return value.to_string(),
+    };
+
+    PHYSICAL_TYPES.with_borrow_mut(|types| {
+        let typ = types
+            .entry(full_name.to_string())
+            .or_insert_with(|| vhpi::PhysicalType::from_handle(obj).ok());
+        let Some(typ) = typ else {
+            return value.to_string();
+        };
+        let values: Vec<String> = raw
+            .into_iter()
+            .map(|phys| typ.value(phys).to_string())
+            .collect();
+        if is_vec {
+            format!("[{}]", values.join(", "))
+        } else {
+            values.concat()
+        }
+    })
+}
+
 fn value_change(data: &vhpi::CbData) {
     let obj = data.obj();
     let val = obj.get_value(vhpi::Format::ObjType);
     match val {
         Ok(value) => {
             let full_name = obj.get_full_name().unwrap();
-            vhpi::printf!("value change {} => {}", full_name, value);
+            vhpi::printf!(
+                "value change {} => {}",
+                full_name,
+                format_value(obj, &full_name, &value)
+            );
         }
         Err(err) => {
             let full_name = obj.get_full_name().unwrap_or_else(|| "unknown".to_string());
@@ -62,6 +112,7 @@ fn value_change(data: &vhpi::CbData) {
     }
 }
 
+#[allow(clippy::option_map_unit_fn)]
 fn walk_region(region: &vhpi::Handle) {
     region
         .get_kind()
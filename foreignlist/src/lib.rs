@@ -0,0 +1,34 @@
+use vhpi::{startup_routines, CbData, CbReason, Severity};
+
+fn end_of_elaboration(_data: &CbData) {
+    let models = vhpi::foreign_models();
+    vhpi::printf!("foreignlist: {} registered foreign models", models.len());
+    for model in &models {
+        vhpi::printf!(
+            "foreignlist: {:?} model \"VHPI {} {}\"",
+            model.kind,
+            model.library_name.as_deref().unwrap_or("?"),
+            model.model_name.as_deref().unwrap_or("?")
+        );
+    }
+
+    let mismatches = vhpi::check_foreign_bindings();
+    for mismatch in &mismatches {
+        vhpi::assert(Severity::Error, mismatch.to_string());
+    }
+    vhpi::printf!(
+        "foreignlist: {} foreign attribute mismatches",
+        mismatches.len()
+    );
+}
+
+extern "C" fn foreignlist_startup() {
+    vhpi::printf!("foreignlist plugin loaded");
+    if let Err(err) = vhpi::register_cb(CbReason::EndOfElaboration, end_of_elaboration) {
+        vhpi::printf!("foreignlist: failed to register callback: {:?}", err);
+    }
+}
+
+startup_routines! {
+    foreignlist_startup,
+}
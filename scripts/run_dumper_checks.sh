@@ -13,6 +13,12 @@ EXPECTED_MARKERS=(
   "end of simulation"
 )
 
+# Extra markers for individual testbenches, separated by `|` and matched
+# ignoring case since the simulator may report names in upper case.
+declare -A TB_MARKERS=(
+  [tb_physical_time]="s_freq => 100 MHz$|s_freq => 100 THz$|s_voltage => 3300 mV$|s_voltage => 2 V$|v_freq_arr => \\[50 MHz, 100 MHz, 150 MHz\\]"
+)
+
 TEST_BENCHES=(
   tb_simple
   tb_string
@@ -167,6 +173,15 @@ for tb in "${TEST_BENCHES[@]}"; do
     fi
   done
 
+  IFS='|' read -r -a tb_markers <<<"${TB_MARKERS[$tb]:-}"
+  for marker in "${tb_markers[@]}"; do
+    if ! grep -Eiq "$marker" "$LOG_FILE"; then
+      echo "${tb}: missing marker /${marker}/" >&2
+      cat "$LOG_FILE" >&2
+      exit 1
+    fi
+  done
+
   if [[ "$SHOW_LOG" == "true" ]]; then
     echo "----- begin ${LOG_FILE} -----"
     cat "$LOG_FILE"
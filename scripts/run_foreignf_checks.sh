@@ -9,7 +9,9 @@ SHOW_LOG="false"
 WORK_ROOT="${ROOT_DIR}/target/nvc-work"
 EXPECTED_MARKERS=(
     "foreignf plugin loaded"
-  "foreignf: all checks passed \(4 mark_call invocations, 4 add_ints invocations, 6 bit_reverse invocations\)"
+  "foreignf: div_mod caller location .*tb_foreignf.vhdl:76: call to "
+  "foreignf: backtrace checks passed"
+  "foreignf: all checks passed \(4 mark_call invocations, 4 add_ints invocations, 6 bit_reverse invocations, 2 invocations of each #\[foreign\] model, 3 to_hex_string invocations, 3 div_mod invocations, 2 record invocations\)"
 )
 
 TEST_BENCH="tb_foreignf"
@@ -0,0 +1,30 @@
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity tb_batch is
+end entity;
+
+architecture sim of tb_batch is
+  -- None of these signals has a driver, so the values the batch plugin
+  -- deposits at 1 ns stay in place for the checks below.
+  signal wide   : std_logic_vector(11 downto 0) := "101001011100";
+  signal narrow : std_logic_vector(1 downto 0) := "10";
+  signal count  : integer := 42;
+  signal mid    : std_logic_vector(3 downto 0) := "01ZX";
+  signal flag   : std_logic := '1';
+begin
+  check : process
+  begin
+    wait for 2 ns;
+    assert wide = "000011110000"
+      report "write_many did not update wide"
+      severity failure;
+    assert narrow = "01"
+      report "write_many did not update narrow"
+      severity failure;
+    assert mid = "1100"
+      report "write_many did not update mid"
+      severity failure;
+    wait;
+  end process;
+end architecture;
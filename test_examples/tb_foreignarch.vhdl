@@ -0,0 +1,42 @@
+entity accum is
+  generic (gain : integer);
+  port (d : in integer; q : out integer);
+end entity;
+
+architecture rust of accum is
+  attribute foreign of rust : architecture is "VHPI rust_vhpi_arch accum";
+begin
+end architecture;
+
+entity tb_foreignarch is
+end entity;
+
+architecture sim of tb_foreignarch is
+  signal d      : integer := 0;
+  signal q_one  : integer;
+  signal q_ten  : integer;
+begin
+  u_one : entity work.accum
+    generic map (gain => 1)
+    port map (d => d, q => q_one);
+
+  u_ten : entity work.accum
+    generic map (gain => 10)
+    port map (d => d, q => q_ten);
+
+  stim : process
+  begin
+    wait for 1 ns;
+    d <= 2;
+    wait for 1 ns;
+    d <= 3;
+    wait for 1 ns;
+    assert q_one = 5
+      report "u_one accumulated " & integer'image(q_one) & ", expected 5"
+      severity failure;
+    assert q_ten = 50
+      report "u_ten accumulated " & integer'image(q_ten) & ", expected 50"
+      severity failure;
+    wait;
+  end process;
+end architecture;
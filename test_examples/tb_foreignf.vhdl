@@ -5,10 +5,24 @@ entity tb_foreignf is
 end entity;
 
 architecture sim of tb_foreignf is
+    type header_t is record
+        src   : integer;
+        dst   : integer;
+        valid : boolean;
+    end record;
+
+    type header_array_t is array (natural range <>) of header_t;
+
     procedure mark_call;
     function add_ints(a : integer; b : integer) return integer;
     procedure bit_reverse_foreign(v_in : in std_logic_vector; v_out : out std_logic_vector);
     function bit_reverse(v : std_logic_vector) return std_logic_vector;
+    function to_hex_string(x : integer) return string;
+    function to_hex_string_handle(x : integer) return string;
+    procedure div_mod(a, b : in integer; q, r : out integer);
+    procedure trace_stack;
+    procedure swap_header(hdr : inout header_t);
+    function count_valid(hdrs : header_array_t) return integer;
 
     procedure mark_call is
     begin
@@ -32,6 +46,89 @@ architecture sim of tb_foreignf is
             severity failure;
     end procedure;
 
+    function to_hex_string(x : integer) return string is
+    begin
+        assert false
+            report "foreign binding for to_hex_string was not resolved"
+            severity failure;
+        return "";
+    end function;
+
+    function to_hex_string_handle(x : integer) return string is
+    begin
+        assert false
+            report "foreign binding for to_hex_string_handle was not resolved"
+            severity failure;
+        return "";
+    end function;
+
+    procedure div_mod(a, b : in integer; q, r : out integer) is
+    begin
+        assert false
+            report "foreign binding for div_mod was not resolved"
+            severity failure;
+    end procedure;
+
+    -- Calls div_mod from a subprogram frame so that the call site is
+    -- known; foreignf checks the reported line against this call.
+    procedure div_mod_wrapped(a, b : in integer; q, r : out integer) is
+    begin
+        div_mod(a, b, q, r);
+    end procedure;
+
+    procedure trace_stack is
+    begin
+        assert false
+            report "foreign binding for trace_stack was not resolved"
+            severity failure;
+    end procedure;
+
+    -- trace_stack captures a backtrace from two levels of VHDL calls and
+    -- checks the frame order and the locals of each frame.
+    procedure trace_inner(depth : in integer) is
+        variable inner_var : integer := 7;
+    begin
+        trace_stack;
+    end procedure;
+
+    procedure trace_outer(count : in integer) is
+        variable outer_var : integer := 3;
+    begin
+        trace_inner(count + 1);
+    end procedure;
+
+    -- Implemented with #[vhpi::foreign] rather than a hand-written exec
+    -- callback.
+    function add_ints_macro(a : integer; b : integer) return integer is
+    begin
+        assert false
+            report "foreign binding for add_ints_macro was not resolved"
+            severity failure;
+        return 0;
+    end function;
+
+    procedure bit_reverse_macro(v_in : in std_logic_vector; v_out : out std_logic_vector) is
+    begin
+        assert false
+            report "foreign binding for bit_reverse_macro was not resolved"
+            severity failure;
+    end procedure;
+
+    procedure swap_header(hdr : inout header_t) is
+    begin
+        assert false
+            report "foreign binding for swap_header was not resolved"
+            severity failure;
+    end procedure;
+
+    function count_valid(hdrs : header_array_t) return integer is
+    begin
+        assert false
+            report "foreign binding for count_valid was not resolved"
+            severity failure;
+        return -1;
+    end function;
+
     function bit_reverse(v : std_logic_vector) return std_logic_vector is
         variable ret : std_logic_vector(v'range);
     begin
@@ -43,6 +140,14 @@ architecture sim of tb_foreignf is
     attribute foreign of mark_call : procedure is "VHPI rust_vhpi_tests mark_call";
     attribute foreign of add_ints : function is "VHPI rust_vhpi_tests add_ints";
     attribute foreign of bit_reverse_foreign : procedure is "VHPI rust_vhpi_tests bit_reverse";
+    attribute foreign of add_ints_macro : function is "VHPI rust_vhpi_tests add_ints_macro";
+    attribute foreign of bit_reverse_macro : procedure is "VHPI rust_vhpi_tests bit_reverse_macro";
+    attribute foreign of to_hex_string : function is "VHPI rust_vhpi_tests to_hex_string";
+    attribute foreign of to_hex_string_handle : function is "VHPI rust_vhpi_tests to_hex_string_handle";
+    attribute foreign of div_mod : procedure is "VHPI rust_vhpi_tests div_mod";
+    attribute foreign of trace_stack : procedure is "VHPI rust_vhpi_tests trace_stack";
+    attribute foreign of swap_header : procedure is "VHPI rust_vhpi_tests swap_header";
+    attribute foreign of count_valid : function is "VHPI rust_vhpi_tests count_valid";
 begin
     stim : process
         variable result : integer;
@@ -52,6 +157,8 @@ begin
         variable rev4 : std_logic_vector(3 downto 0);
         variable rev8 : std_logic_vector(7 downto 0);
         variable rev13 : std_logic_vector(12 downto 0);
+        variable quot, remd : integer;
+        variable hdr : header_t;
     begin
         rev1 := bit_reverse("1");
         assert rev1 = "1"
@@ -83,6 +190,71 @@ begin
             report "bit_reverse length-13 with mixed std_logic values failed"
             severity failure;
 
+        assert to_hex_string(0) = "0"
+            report "to_hex_string(0) failed"
+            severity failure;
+        assert to_hex_string(255) = "FF"
+            report "to_hex_string(255) failed"
+            severity failure;
+        assert to_hex_string(48879) = "BEEF"
+            report "to_hex_string(48879) failed"
+            severity failure;
+
+        assert to_hex_string_handle(0) = "0"
+            report "to_hex_string_handle(0) failed"
+            severity failure;
+        assert to_hex_string_handle(255) = "FF"
+            report "to_hex_string_handle(255) failed"
+            severity failure;
+        assert to_hex_string_handle(48879) = "BEEF"
+            report "to_hex_string_handle(48879) failed"
+            severity failure;
+
+        div_mod(17, 5, quot, remd);
+        assert quot = 3 and remd = 2
+            report "div_mod(17, 5) returned " & integer'image(quot) & ", " & integer'image(remd)
+            severity failure;
+        div_mod(42, 7, quot, remd);
+        assert quot = 6 and remd = 0
+            report "div_mod(42, 7) returned " & integer'image(quot) & ", " & integer'image(remd)
+            severity failure;
+        div_mod_wrapped(9, 4, quot, remd);
+        assert quot = 2 and remd = 1
+            report "div_mod_wrapped(9, 4) returned " & integer'image(quot) & ", " & integer'image(remd)
+            severity failure;
+
+        trace_outer(41);
+
+        hdr := (src => 10, dst => 20, valid => false);
+        swap_header(hdr);
+        assert hdr = (src => 20, dst => 10, valid => true)
+            report "swap_header returned " & integer'image(hdr.src) & ", "
+                & integer'image(hdr.dst) & ", " & boolean'image(hdr.valid)
+            severity failure;
+
+        result := count_valid((hdr, (src => 1, dst => 2, valid => false), hdr));
+        assert result = 2
+            report "count_valid returned " & integer'image(result)
+            severity failure;
+
+        result := add_ints_macro(20, 22);
+        assert result = 42
+            report "add_ints_macro(20, 22) returned " & integer'image(result)
+            severity failure;
+        result := add_ints_macro(-3, 1);
+        assert result = -2
+            report "add_ints_macro(-3, 1) returned " & integer'image(result)
+            severity failure;
+
+        bit_reverse_macro("1100", rev4);
+        assert rev4 = "0011"
+            report "bit_reverse_macro length-4 failed"
+            severity failure;
+        bit_reverse_macro("10ZX01-1", rev8);
+        assert rev8 = "1-10XZ01"
+            report "bit_reverse_macro length-8 failed"
+            severity failure;
+
         result := add_ints(1, 2);
         assert result = 3 report "add_ints(1, 2) returned " & integer'image(result) severity failure;
         mark_call;
@@ -0,0 +1,24 @@
+entity tb_foreignlist is
+end entity;
+
+architecture sim of tb_foreignlist is
+  -- The library name is misspelled, so no registered model can match.
+  function add_ints(a : integer; b : integer) return integer;
+  attribute foreign of add_ints : function is "VHPI rust_vhpi_tsets add_ints";
+
+  function add_ints(a : integer; b : integer) return integer is
+  begin
+    assert false
+      report "add_ints must not be called"
+      severity failure;
+    return 0;
+  end function;
+begin
+  -- add_ints is never called, so only the end of elaboration check can
+  -- find the misspelling.
+  idle : process
+  begin
+    wait for 1 ns;
+    wait;
+  end process;
+end architecture;
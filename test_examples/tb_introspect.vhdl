@@ -0,0 +1,31 @@
+entity tb_introspect is
+end entity;
+
+architecture sim of tb_introspect is
+  attribute monitor : boolean;
+
+  signal s_watched : bit := '0';
+  signal s_plain   : bit := '0';
+  signal s_follow  : bit := '0';
+
+  attribute monitor of s_watched : signal is true;
+begin
+  stim : process
+  begin
+    wait for 5 ns;
+    s_watched <= '1';
+    s_plain <= '1';
+    wait;
+  end process;
+
+  follower : process (s_watched)
+  begin
+    s_follow <= s_watched;
+  end process;
+
+  ticker : process
+  begin
+    wait for 3 ns;
+    wait;
+  end process;
+end architecture;
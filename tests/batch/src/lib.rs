@@ -0,0 +1,135 @@
+use std::cell::Cell;
+
+use vhpi::{
+    startup_routines, CbData, CbReason, Error, Format, Handle, LogicVal, LogicVec, OneToOne,
+    PutValueMode, Time, Value,
+};
+
+const BATCH_TIME_FS: i64 = 1_000_000;
+const READBACK_DELAY_FS: i64 = 500_000;
+
+/// Signals in `tb_batch.vhdl`, ordered so that each read or write changes
+/// the element count of the shared buffers.
+const SIGNALS: [&str; 5] = ["wide", "narrow", "count", "mid", "flag"];
+
+/// New values written with `write_many`: 12, then 2, then 4 elements.
+const WRITES: [(&str, &str); 3] = [("wide", "000011110000"), ("narrow", "01"), ("mid", "1100")];
+
+thread_local! {
+    static READS_CHECKED: Cell<bool> = const { Cell::new(false) };
+    static SNAPSHOT_CHECKED: Cell<bool> = const { Cell::new(false) };
+    static WRITES_CHECKED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn signal(name: &str) -> Handle {
+    vhpi::handle(OneToOne::RootInst)
+        .handle_by_name(name)
+        .unwrap_or_else(|| panic!("signal {name} not found"))
+}
+
+fn logic(bits: &str) -> Value {
+    Value::LogicVec(LogicVec::from(bits))
+}
+
+fn check_initial(values: &[Result<Value, Error>], what: &str) {
+    let values: Vec<&Value> = values
+        .iter()
+        .map(|value| {
+            value
+                .as_ref()
+                .unwrap_or_else(|err| panic!("batch: {what} failed: {err}"))
+        })
+        .collect();
+    assert_eq!(
+        values[..4],
+        [
+            &logic("101001011100"),
+            &logic("10"),
+            &Value::Int(42),
+            &logic("01ZX")
+        ],
+        "batch: {what} returned wrong vectors"
+    );
+    assert!(
+        matches!(
+            values[4],
+            Value::Logic(LogicVal::One) | Value::Enum(3) | Value::SmallEnum(3)
+        ),
+        "batch: {what} returned {:?} for flag",
+        values[4]
+    );
+}
+
+fn check_reads(handles: &[Handle]) {
+    check_initial(&vhpi::read_many(handles, Format::ObjType), "read_many");
+    READS_CHECKED.set(true);
+    vhpi::printf!("batch: read_many checks passed");
+
+    vhpi::snapshot_many(handles.to_vec(), Format::ObjType, |values| {
+        check_initial(&values, "snapshot_many");
+        SNAPSHOT_CHECKED.set(true);
+        vhpi::printf!("batch: snapshot_many checks passed");
+    })
+    .expect("batch: failed to register snapshot");
+}
+
+fn check_writes() {
+    let handles: Vec<Handle> = WRITES.iter().map(|&(name, _)| signal(name)).collect();
+    let results = vhpi::write_many(
+        handles
+            .iter()
+            .zip(WRITES)
+            .map(|(handle, (_, bits))| (handle, logic(bits))),
+        PutValueMode::DepositPropagate,
+    );
+    for ((name, _), result) in WRITES.iter().zip(&results) {
+        if let Err(err) = result {
+            panic!("batch: write_many failed for {name}: {err}");
+        }
+    }
+
+    vhpi::register_cb_after_delay(Time::from(READBACK_DELAY_FS), move |_| {
+        let values = vhpi::read_many(&handles, Format::LogicVec);
+        for ((name, bits), value) in WRITES.iter().zip(values) {
+            assert_eq!(
+                value.ok(),
+                Some(logic(bits)),
+                "batch: {name} does not hold the value written by write_many"
+            );
+        }
+        WRITES_CHECKED.set(true);
+        vhpi::printf!("batch: write_many checks passed");
+    })
+    .expect("batch: failed to register read-back callback");
+}
+
+fn start_of_sim(_data: &CbData) {
+    vhpi::register_cb_after_delay(Time::from(BATCH_TIME_FS), |_| {
+        let handles: Vec<Handle> = SIGNALS.iter().map(|name| signal(name)).collect();
+        check_reads(&handles);
+        check_writes();
+    })
+    .expect("batch: failed to register batch callback");
+}
+
+fn end_of_sim(_data: &CbData) {
+    assert!(READS_CHECKED.get(), "batch: read_many was not checked");
+    assert!(
+        SNAPSHOT_CHECKED.get(),
+        "batch: snapshot_many was not checked"
+    );
+    assert!(WRITES_CHECKED.get(), "batch: write_many was not checked");
+    vhpi::printf!("batch: all checks passed");
+}
+
+#[no_mangle]
+pub extern "C" fn batch_startup() {
+    vhpi::printf!("batch plugin loaded");
+
+    let _ = vhpi::register_cb(CbReason::StartOfSimulation, start_of_sim);
+    let _ = vhpi::register_cb(CbReason::EndOfSimulation, end_of_sim);
+}
+
+startup_routines! {
+    batch_startup,
+}
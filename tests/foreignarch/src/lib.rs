@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+
+use vhpi::{
+    startup_routines, CbData, CbReason, ConstantValue, Error, ForeignArchitecture, ForeignPorts,
+    Value,
+};
+
+/// Expected (gain, final total, evaluate calls) per instance: one call at
+/// start of simulation and one for each of the two changes on `d`.
+const EXPECTED: [(i32, i32, usize); 2] = [(1, 5, 3), (10, 50, 3)];
+
+/// What each instance has seen so far, indexed by its slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Summary {
+    gain: i32,
+    total: i32,
+    evaluations: usize,
+}
+
+thread_local! {
+    static SUMMARIES: RefCell<Vec<Summary>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Adds `gain * d` to a running total on every change of `d`.
+struct Accum {
+    slot: usize,
+    gain: i32,
+    total: i32,
+}
+
+impl ForeignArchitecture for Accum {
+    fn elaborate(_ports: &ForeignPorts, generics: &[ConstantValue]) -> Result<Self, Error> {
+        let gain = generics
+            .iter()
+            .find(|generic| generic.name.eq_ignore_ascii_case("gain"))
+            .ok_or_else(|| Error::from("accum: missing generic gain"))?;
+        let Ok(Value::Int(gain)) = gain.value else {
+            return Err(format!("accum: unexpected gain {:?}", gain.value)
+                .as_str()
+                .into());
+        };
+
+        let slot = SUMMARIES.with_borrow_mut(|summaries| {
+            summaries.push(Summary {
+                gain,
+                total: 0,
+                evaluations: 0,
+            });
+            summaries.len() - 1
+        });
+        vhpi::printf!("foreignarch: elaborated accum with gain {}", gain);
+        Ok(Accum {
+            slot,
+            gain,
+            total: 0,
+        })
+    }
+
+    fn evaluate(&mut self, ports: &ForeignPorts) -> Result<(), Error> {
+        let Value::Int(d) = ports.read("d")? else {
+            return Err("accum: port d is not an integer".into());
+        };
+        self.total += self.gain * d;
+        ports.write("q", Value::Int(self.total))?;
+
+        SUMMARIES.with_borrow_mut(|summaries| {
+            let summary = &mut summaries[self.slot];
+            summary.total = self.total;
+            summary.evaluations += 1;
+        });
+        Ok(())
+    }
+}
+
+fn end_of_sim(_data: &CbData) {
+    let mut summaries = SUMMARIES.with_borrow(Clone::clone);
+    summaries.sort_by_key(|summary| summary.gain);
+
+    let expected: Vec<Summary> = EXPECTED
+        .iter()
+        .map(|&(gain, total, evaluations)| Summary {
+            gain,
+            total,
+            evaluations,
+        })
+        .collect();
+    assert_eq!(
+        summaries, expected,
+        "foreignarch: instances did not keep separate state"
+    );
+
+    vhpi::printf!(
+        "foreignarch: all checks passed ({} instances)",
+        summaries.len()
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn foreignarch_startup() {
+    vhpi::printf!("foreignarch plugin loaded");
+
+    vhpi::register_foreign_arch::<Accum>("rust_vhpi_arch", "accum")
+        .expect("failed to register foreign architecture accum");
+    let _ = vhpi::register_cb(CbReason::EndOfSimulation, end_of_sim);
+}
+
+startup_routines! {
+    foreignarch_startup,
+}
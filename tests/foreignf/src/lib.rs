@@ -1,34 +1,130 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{LazyLock, Mutex};
-
 use vhpi::{
-    startup_routines, CbData, CbReason, ForeignData, ForeignExecData, ForeignKind, Format,
-    OneToMany, PutValueMode, Value,
+    startup_routines, Backtrace, Error, ForeignCallInfo, ForeignData, ForeignExecData, ForeignKind,
+    ForeignLibrary, Format, LogicVec, Mode, OneToMany, PutValueMode, ReturnPath, StackFrame, Value,
 };
 
 const EXPECTED_CALL_TIMES_FS: [i64; 4] = [0, 5_000_000, 10_000_000, 15_000_000];
 const EXPECTED_ADD_CALLS: usize = 4;
 const EXPECTED_BIT_REVERSE_CALLS: usize = 6;
+const EXPECTED_MACRO_CALLS: usize = 2;
+const EXPECTED_HEX_STRING_CALLS: usize = 3;
+const EXPECTED_DIV_MOD_CALLS: usize = 3;
+/// Line of the `stim` process declaration in `tb_foreignf.vhdl`.
+const STIM_PROCESS_LINE: u32 = 152;
+/// Line of the `div_mod` call inside `div_mod_wrapped` in `tb_foreignf.vhdl`.
+const DIV_MOD_WRAPPED_CALL_LINE: u32 = 76;
+const EXPECTED_HEADER_CALLS: usize = 2;
+const EXPECTED_BACKTRACES: usize = 1;
+/// Line of the `trace_stack` call inside `trace_inner` in `tb_foreignf.vhdl`.
+const TRACE_STACK_CALL_LINE: u32 = 91;
+/// Line of the `trace_inner` call inside `trace_outer` in `tb_foreignf.vhdl`.
+const TRACE_INNER_CALL_LINE: u32 = 97;
+
+/// Counters shared by the foreign subprograms of this library.
+#[derive(Default)]
+struct TestLibrary {
+    mark_call_times_fs: Vec<i64>,
+    add_calls: usize,
+    bit_reverse_calls: usize,
+    add_macro_calls: usize,
+    bit_reverse_macro_calls: usize,
+    hex_string_calls: usize,
+    hex_string_handle_calls: usize,
+    div_mod_calls: usize,
+    header_calls: usize,
+    backtraces: usize,
+}
 
-static CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
-static OBSERVED_CALL_TIMES_FS: LazyLock<Mutex<Vec<i64>>> = LazyLock::new(|| Mutex::new(Vec::new()));
-static ADD_CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
-static BIT_REVERSE_CALL_COUNT: AtomicUsize = AtomicUsize::new(0);
+impl ForeignLibrary for TestLibrary {
+    fn on_load() -> Result<Self, Error> {
+        vhpi::printf!("foreignf plugin loaded");
+        Ok(TestLibrary::default())
+    }
+
+    fn on_elaboration(&mut self) {
+        let mismatches = vhpi::check_foreign_bindings();
+        assert!(
+            mismatches.is_empty(),
+            "foreignf: unexpected foreign attribute mismatches: {}",
+            mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
+    }
+
+    fn on_shutdown(&mut self) {
+        assert_eq!(
+            self.mark_call_times_fs, EXPECTED_CALL_TIMES_FS,
+            "foreignf: unexpected call times"
+        );
+        assert_eq!(
+            self.add_calls, EXPECTED_ADD_CALLS,
+            "foreignf: expected {EXPECTED_ADD_CALLS} add_ints calls, got {}",
+            self.add_calls
+        );
+        assert_eq!(
+            self.bit_reverse_calls, EXPECTED_BIT_REVERSE_CALLS,
+            "foreignf: expected {EXPECTED_BIT_REVERSE_CALLS} bit_reverse calls, got {}",
+            self.bit_reverse_calls
+        );
+        assert_eq!(
+            (self.add_macro_calls, self.bit_reverse_macro_calls),
+            (EXPECTED_MACRO_CALLS, EXPECTED_MACRO_CALLS),
+            "foreignf: expected {EXPECTED_MACRO_CALLS} calls of each #[foreign] model"
+        );
+        assert_eq!(
+            self.hex_string_calls, EXPECTED_HEX_STRING_CALLS,
+            "foreignf: expected {EXPECTED_HEX_STRING_CALLS} to_hex_string calls, got {}",
+            self.hex_string_calls
+        );
+        assert_eq!(
+            self.hex_string_handle_calls, EXPECTED_HEX_STRING_CALLS,
+            "foreignf: expected {EXPECTED_HEX_STRING_CALLS} to_hex_string_handle calls, got {}",
+            self.hex_string_handle_calls
+        );
+        assert_eq!(
+            self.div_mod_calls, EXPECTED_DIV_MOD_CALLS,
+            "foreignf: expected {EXPECTED_DIV_MOD_CALLS} div_mod calls, got {}",
+            self.div_mod_calls
+        );
+        assert_eq!(
+            self.backtraces, EXPECTED_BACKTRACES,
+            "foreignf: expected {EXPECTED_BACKTRACES} backtraces, got {}",
+            self.backtraces
+        );
+        assert_eq!(
+            self.header_calls, EXPECTED_HEADER_CALLS,
+            "foreignf: expected {EXPECTED_HEADER_CALLS} record parameter calls, got {}",
+            self.header_calls
+        );
+
+        vhpi::printf!(
+            "foreignf: all checks passed ({} mark_call invocations, {} add_ints invocations, {} bit_reverse invocations, {} invocations of each #[foreign] model, {} to_hex_string invocations, {} div_mod invocations, {} record invocations)",
+            EXPECTED_CALL_TIMES_FS.len(),
+            EXPECTED_ADD_CALLS,
+            EXPECTED_BIT_REVERSE_CALLS,
+            EXPECTED_MACRO_CALLS,
+            EXPECTED_HEX_STRING_CALLS,
+            EXPECTED_DIV_MOD_CALLS,
+            EXPECTED_HEADER_CALLS
+        );
+    }
+}
+
+fn with_state<R>(f: impl FnOnce(&mut TestLibrary) -> R) -> R {
+    vhpi::with_library(f).expect("foreignf: library context is unavailable")
+}
 
 unsafe extern "C" fn mark_call_exec(_call_data: &ForeignExecData) {
-    let call_index = CALL_COUNT.fetch_add(1, Ordering::SeqCst);
     let now = vhpi::get_time();
+    let call_count = with_state(|state| {
+        state.mark_call_times_fs.push(now.to_i64());
+        state.mark_call_times_fs.len()
+    });
 
-    OBSERVED_CALL_TIMES_FS
-        .lock()
-        .expect("poisoned observed-times mutex")
-        .push(now.to_i64());
-
-    vhpi::printf!(
-        "foreignf: mark_call invocation {} at {}",
-        call_index + 1,
-        now
-    );
+    vhpi::printf!("foreignf: mark_call invocation {} at {}", call_count, now);
 }
 
 unsafe extern "C" fn add_ints_exec(call_data: &ForeignExecData) {
@@ -65,10 +161,13 @@ unsafe extern "C" fn add_ints_exec(call_data: &ForeignExecData) {
         .put_value(Value::Int(sum), PutValueMode::Deposit)
         .expect("foreignf: failed to write add_ints return value");
 
-    let call_index = ADD_CALL_COUNT.fetch_add(1, Ordering::SeqCst);
+    let call_count = with_state(|state| {
+        state.add_calls += 1;
+        state.add_calls
+    });
     vhpi::printf!(
         "foreignf: add_ints invocation {}: {} + {} = {}",
-        call_index + 1,
+        call_count,
         a,
         b,
         sum
@@ -103,52 +202,244 @@ unsafe extern "C" fn bit_reverse_exec(call_data: &ForeignExecData) {
             .expect("foreignf: failed to write bit_reverse output value");
     }
 
-    let call_index = BIT_REVERSE_CALL_COUNT.fetch_add(1, Ordering::SeqCst);
-    vhpi::printf!("foreignf: bit_reverse invocation {}", call_index + 1);
+    let call_count = with_state(|state| {
+        state.bit_reverse_calls += 1;
+        state.bit_reverse_calls
+    });
+    vhpi::printf!("foreignf: bit_reverse invocation {}", call_count);
+}
+
+// The same models as add_ints and bit_reverse, generated by #[foreign].
+#[vhpi::foreign(library = "rust_vhpi_tests")]
+fn add_ints_macro(a: i32, b: i32) -> i32 {
+    let call_count = with_state(|state| {
+        state.add_macro_calls += 1;
+        state.add_macro_calls
+    });
+    vhpi::printf!("foreignf: add_ints_macro invocation {}", call_count);
+
+    a + b
 }
 
-fn end_of_sim(_data: &CbData) {
-    let call_count = CALL_COUNT.load(Ordering::SeqCst);
+#[vhpi::foreign(library = "rust_vhpi_tests")]
+fn bit_reverse_macro(bits: LogicVec, reversed: &mut LogicVec) {
     assert_eq!(
-        call_count,
-        EXPECTED_CALL_TIMES_FS.len(),
-        "foreignf: expected {} calls, got {}",
-        EXPECTED_CALL_TIMES_FS.len(),
-        call_count
+        bits.len(),
+        reversed.len(),
+        "foreignf: bit_reverse_macro output length mismatch"
     );
+    *reversed = bits.reverse();
+
+    let call_count = with_state(|state| {
+        state.bit_reverse_macro_calls += 1;
+        state.bit_reverse_macro_calls
+    });
+    vhpi::printf!("foreignf: bit_reverse_macro invocation {}", call_count);
+}
 
-    let observed_times = OBSERVED_CALL_TIMES_FS
-        .lock()
-        .expect("poisoned observed-times mutex")
-        .clone();
+#[vhpi::foreign(library = "rust_vhpi_tests")]
+fn to_hex_string(x: i32) -> String {
+    let call_count = with_state(|state| {
+        state.hex_string_calls += 1;
+        state.hex_string_calls
+    });
+    vhpi::printf!("foreignf: to_hex_string invocation {}", call_count);
+
+    format!("{x:X}")
+}
+
+// The simulator provides no buffer for the unconstrained result, so
+// put_return_value must fall back to writing through the function handle.
+unsafe extern "C" fn to_hex_string_handle_exec(call_data: &ForeignExecData) {
+    let x = match call_data
+        .get_foreignf_arg(0, Format::Int)
+        .expect("foreignf: failed to read to_hex_string_handle parameter")
+    {
+        Value::Int(v) => v,
+        other => panic!("foreignf: to_hex_string_handle parameter is not integer: {other:?}"),
+    };
+
+    let path = call_data
+        .put_return_value(Value::Str(format!("{x:X}")))
+        .expect("foreignf: failed to write to_hex_string_handle return value");
     assert_eq!(
-        observed_times, EXPECTED_CALL_TIMES_FS,
-        "foreignf: unexpected call times"
+        path,
+        ReturnPath::Handle,
+        "foreignf: to_hex_string_handle result was not written by handle"
     );
 
-    let add_calls = ADD_CALL_COUNT.load(Ordering::SeqCst);
+    let call_count = with_state(|state| {
+        state.hex_string_handle_calls += 1;
+        state.hex_string_handle_calls
+    });
+    vhpi::printf!("foreignf: to_hex_string_handle invocation {}", call_count);
+}
+
+// Reads and writes parameters by name rather than position.
+unsafe extern "C" fn div_mod_exec(call_data: &ForeignExecData) {
+    let info = call_data.call_info();
+    let (Some(Value::Int(a)), Some(Value::Int(b))) = (call_data.arg("a"), call_data.arg("b"))
+    else {
+        panic!("foreignf: {info}: failed to read div_mod parameters");
+    };
+
+    assert!(
+        info.subprogram.eq_ignore_ascii_case("div_mod"),
+        "foreignf: unexpected subprogram in call info: {info}"
+    );
+    let modes: Vec<_> = info.params.iter().map(|param| param.mode.clone()).collect();
     assert_eq!(
-        add_calls, EXPECTED_ADD_CALLS,
-        "foreignf: expected {EXPECTED_ADD_CALLS} add_ints calls, got {add_calls}"
+        modes,
+        [
+            Some(Mode::In),
+            Some(Mode::In),
+            Some(Mode::Out),
+            Some(Mode::Out)
+        ],
+        "foreignf: unexpected div_mod parameters in call info: {info}"
     );
+    check_div_mod_caller(&info);
+
+    call_data
+        .set_arg("q", Value::Int(a / b))
+        .expect("foreignf: failed to write div_mod quotient");
+    call_data
+        .set_arg("r", Value::Int(a % b))
+        .expect("foreignf: failed to write div_mod remainder");
 
-    let bit_reverse_calls = BIT_REVERSE_CALL_COUNT.load(Ordering::SeqCst);
+    assert!(
+        call_data.set_arg("a", Value::Int(0)).is_err(),
+        "foreignf: writing an in parameter should fail"
+    );
+    assert!(
+        call_data.set_arg("missing", Value::Int(0)).is_err(),
+        "foreignf: writing an unknown parameter should fail"
+    );
+
+    let call_count = with_state(|state| {
+        state.div_mod_calls += 1;
+        state.div_mod_calls
+    });
+    vhpi::printf!("foreignf: div_mod invocation {}", call_count);
+}
+
+// A call from div_mod_wrapped must report the line of the call in its body;
+// a direct call from the process must not report the process declaration.
+fn check_div_mod_caller(info: &ForeignCallInfo) {
+    let caller = info.caller.as_deref().unwrap_or_default();
+    let line = info.location.as_ref().map(|location| location.begin_line);
+    if caller.to_ascii_lowercase().contains("div_mod_wrapped") {
+        assert!(
+            info.location
+                .as_ref()
+                .is_some_and(|location| location.file.ends_with("tb_foreignf.vhdl")),
+            "foreignf: wrapped div_mod call has no call-site file: {info}"
+        );
+        assert_eq!(
+            line,
+            Some(DIV_MOD_WRAPPED_CALL_LINE),
+            "foreignf: wrong call site for wrapped div_mod: {info}"
+        );
+        vhpi::printf!("foreignf: div_mod caller location {}", info);
+    } else {
+        assert!(
+            caller.to_ascii_lowercase().ends_with("stim"),
+            "foreignf: unexpected div_mod caller: {info}"
+        );
+        assert_ne!(
+            line,
+            Some(STIM_PROCESS_LINE),
+            "foreignf: direct div_mod call reported the process location: {info}"
+        );
+    }
+}
+
+/// Integer value of the local `name` in `frame`.
+fn frame_int(frame: &StackFrame, name: &str) -> Option<i32> {
+    match frame.local(name)?.value? {
+        Value::Int(value) => Some(value),
+        _ => None,
+    }
+}
+
+// Called from trace_inner, which is called from trace_outer, so the
+// backtrace must list those frames innermost first, each with its own
+// parameters and variables.
+#[vhpi::foreign(library = "rust_vhpi_tests")]
+fn trace_stack() {
+    let backtrace = Backtrace::capture();
+    let frames = backtrace.frames();
+    let names: Vec<String> = frames
+        .iter()
+        .map(|frame| frame.subprogram().unwrap_or_default().to_ascii_lowercase())
+        .collect();
     assert_eq!(
-        bit_reverse_calls, EXPECTED_BIT_REVERSE_CALLS,
-        "foreignf: expected {EXPECTED_BIT_REVERSE_CALLS} bit_reverse calls, got {bit_reverse_calls}"
+        names,
+        ["trace_stack", "trace_inner", "trace_outer"],
+        "foreignf: unexpected backtrace\n{backtrace}"
     );
 
-    vhpi::printf!(
-        "foreignf: all checks passed ({} mark_call invocations, {} add_ints invocations, {} bit_reverse invocations)",
-        EXPECTED_CALL_TIMES_FS.len(),
-        EXPECTED_ADD_CALLS,
-        EXPECTED_BIT_REVERSE_CALLS
+    let (inner, outer) = (&frames[1], &frames[2]);
+    assert_eq!(
+        (inner.line(), outer.line()),
+        (Some(TRACE_STACK_CALL_LINE), Some(TRACE_INNER_CALL_LINE)),
+        "foreignf: backtrace frames report the wrong lines\n{backtrace}"
+    );
+    assert_eq!(
+        (frame_int(inner, "depth"), frame_int(inner, "inner_var")),
+        (Some(42), Some(7)),
+        "foreignf: wrong locals in trace_inner frame"
     );
+    assert_eq!(
+        (frame_int(outer, "count"), frame_int(outer, "outer_var")),
+        (Some(41), Some(3)),
+        "foreignf: wrong locals in trace_outer frame"
+    );
+    assert!(
+        inner.local("outer_var").is_none(),
+        "foreignf: trace_inner frame sees the locals of its caller"
+    );
+
+    with_state(|state| state.backtraces += 1);
+    vhpi::printf!("foreignf: backtrace checks passed\n{}", backtrace);
+}
+
+/// Mirrors `header_t` in `tb_foreignf.vhdl`.
+#[derive(vhpi::ForeignComposite)]
+struct Header {
+    src: i32,
+    dst: i32,
+    valid: bool,
+}
+
+#[vhpi::foreign(library = "rust_vhpi_tests")]
+fn swap_header(hdr: &mut Header) {
+    std::mem::swap(&mut hdr.src, &mut hdr.dst);
+    hdr.valid = !hdr.valid;
+
+    let call_count = with_state(|state| {
+        state.header_calls += 1;
+        state.header_calls
+    });
+    vhpi::printf!("foreignf: swap_header invocation {}", call_count);
+}
+
+#[vhpi::foreign(library = "rust_vhpi_tests")]
+fn count_valid(headers: Vec<Header>) -> i32 {
+    let call_count = with_state(|state| {
+        state.header_calls += 1;
+        state.header_calls
+    });
+    vhpi::printf!("foreignf: count_valid invocation {}", call_count);
+
+    let valid = headers.iter().filter(|hdr| hdr.valid).count();
+    i32::try_from(valid).expect("foreignf: too many headers")
 }
 
 #[no_mangle]
 pub extern "C" fn foreignf_startup() {
-    vhpi::printf!("foreignf plugin loaded");
+    vhpi::register_foreign_library::<TestLibrary>(ForeignKind::Lib, "rust_vhpi_tests", "foreignf")
+        .expect("failed to register foreign library");
 
     let registration = vhpi::register_foreignf(
         &ForeignData::new(ForeignKind::Proc, "rust_vhpi_tests", "mark_call").exec(mark_call_exec),
@@ -228,9 +519,24 @@ pub extern "C" fn foreignf_startup() {
         "foreignf: missing bit_reverse exec callback"
     );
 
-    let _ = vhpi::register_cb(CbReason::EndOfSimulation, end_of_sim);
+    vhpi::register_foreignf(
+        &ForeignData::new(ForeignKind::Func, "rust_vhpi_tests", "to_hex_string_handle")
+            .exec(to_hex_string_handle_exec),
+    )
+    .expect("failed to register foreign function to_hex_string_handle");
+
+    vhpi::register_foreignf(
+        &ForeignData::new(ForeignKind::Proc, "rust_vhpi_tests", "div_mod").exec(div_mod_exec),
+    )
+    .expect("failed to register foreign procedure div_mod");
 }
 
 startup_routines! {
     foreignf_startup,
+    register_add_ints_macro,
+    register_bit_reverse_macro,
+    register_to_hex_string,
+    register_swap_header,
+    register_count_valid,
+    register_trace_stack,
 }
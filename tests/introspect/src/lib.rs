@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+
+use vhpi::{
+    startup_routines, CbData, CbReason, Handle, OneToOne, ProcessCallback, ProcessEvent, Trigger,
+    Value,
+};
+
+const WATCHED_EVENT_FS: i64 = 5_000_000;
+const TICKER_TIMEOUT_FS: i64 = 3_000_000;
+
+/// Resumptions seen as (process, time, trigger signal names or `None` for a
+/// timeout).
+type Wakeup = (&'static str, i64, Option<Vec<String>>);
+
+thread_local! {
+    static WAKEUPS: RefCell<Vec<Wakeup>> = const { RefCell::new(Vec::new()) };
+    /// Resume callbacks, kept alive until the end of simulation.
+    static WATCHERS: RefCell<Vec<ProcessCallback>> = const { RefCell::new(Vec::new()) };
+}
+
+fn signal(name: &str) -> Handle {
+    vhpi::handle(OneToOne::RootInst)
+        .handle_by_name(name)
+        .unwrap_or_else(|| panic!("signal {name} not found"))
+}
+
+fn check_attributes() {
+    let watched = signal("s_watched");
+    let plain = signal("s_plain");
+
+    let value = watched
+        .attribute("MONITOR")
+        .expect("failed to read attribute monitor");
+    assert!(
+        matches!(value, Some(Value::Enum(1) | Value::SmallEnum(1))),
+        "introspect: expected monitor = true on s_watched, got {value:?}"
+    );
+
+    let attrs = watched
+        .attributes()
+        .expect("failed to read attributes of s_watched");
+    let monitor = attrs
+        .iter()
+        .find(|attr| attr.name.eq_ignore_ascii_case("monitor"))
+        .expect("introspect: monitor missing from attributes()");
+    assert!(
+        monitor
+            .literal
+            .as_deref()
+            .is_some_and(|lit| lit.eq_ignore_ascii_case("true")),
+        "introspect: unexpected literal {:?}",
+        monitor.literal
+    );
+
+    assert_eq!(
+        plain
+            .attribute("monitor")
+            .expect("failed to look up monitor"),
+        None,
+        "introspect: s_plain should have no monitor attribute"
+    );
+
+    vhpi::printf!("introspect: attribute checks passed");
+}
+
+fn record_wakeup(label: &'static str, event: &ProcessEvent) {
+    let trigger = match event.trigger.as_ref() {
+        Some(Trigger::Event(signals)) => Some(
+            signals
+                .iter()
+                .map(|sig| sig.get_name().unwrap_or_default().to_ascii_lowercase())
+                .collect(),
+        ),
+        Some(Trigger::Timeout) => None,
+        None => panic!("introspect: resume event without trigger"),
+    };
+    WAKEUPS.with(|cell| {
+        cell.borrow_mut()
+            .push((label, event.time.to_i64(), trigger));
+    });
+}
+
+fn watch_processes() {
+    let root = vhpi::handle(OneToOne::RootInst);
+    for label in ["follower", "ticker"] {
+        let watcher = root
+            .handle_by_name(label)
+            .unwrap_or_else(|| panic!("process {label} not found"))
+            .register_resume_cb(move |event| record_wakeup(label, event))
+            .unwrap_or_else(|err| panic!("failed to watch {label}: {err:?}"));
+        WATCHERS.with_borrow_mut(|watchers| watchers.push(watcher));
+    }
+}
+
+fn check_wakeups() {
+    let wakeups = WAKEUPS.with(|cell| cell.borrow().clone());
+
+    let watched = Some(vec!["s_watched".to_string()]);
+    assert!(
+        wakeups.contains(&("follower", WATCHED_EVENT_FS, watched)),
+        "introspect: follower did not report s_watched as its trigger: {wakeups:?}"
+    );
+    assert!(
+        wakeups.contains(&("ticker", TICKER_TIMEOUT_FS, None)),
+        "introspect: ticker did not report a timeout: {wakeups:?}"
+    );
+
+    vhpi::printf!("introspect: process trigger checks passed");
+}
+
+fn start_of_sim(_data: &CbData) {
+    check_attributes();
+    watch_processes();
+}
+
+fn end_of_sim(_data: &CbData) {
+    check_wakeups();
+    for watcher in WATCHERS.take() {
+        watcher
+            .remove()
+            .unwrap_or_else(|err| panic!("introspect: failed to remove resume callback: {err}"));
+    }
+    vhpi::printf!("introspect: all checks passed");
+}
+
+#[no_mangle]
+pub extern "C" fn introspect_startup() {
+    vhpi::printf!("introspect plugin loaded");
+
+    let _ = vhpi::register_cb(CbReason::StartOfSimulation, start_of_sim);
+    let _ = vhpi::register_cb(CbReason::EndOfSimulation, end_of_sim);
+}
+
+startup_routines! {
+    introspect_startup,
+}
@@ -0,0 +1,406 @@
+//! Procedural macros for the `vhpi` crate.
+//!
+//! Use these through the `macros` feature of `vhpi` rather than depending on
+//! this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, FnArg, ItemFn, LitStr, ReturnType, Type};
+
+/// Turn a Rust function into a VHPI foreign function or procedure.
+///
+/// The function is kept as written.  The macro additionally generates an
+/// `extern "C" fn register_<name>()` startup routine which registers the
+/// function with the simulator.
+///
+/// The routine is not collected automatically: each `register_<name>` must
+/// be listed by hand in
+/// [`startup_routines!`](https://docs.rs/vhpi/latest/vhpi/macro.startup_routines.html),
+/// as in the example below.  A foreign subprogram whose routine is missing
+/// from that list is never registered, and the simulator reports it as
+/// unresolved when it elaborates the design.
+///
+/// Arguments:
+///
+/// - `library = "..."` — library name from the VHDL `foreign` attribute
+///   (required).
+/// - `name = "..."` — model name from the `foreign` attribute, defaults to
+///   the function name.
+///
+/// Parameters are read with `vhpi::ForeignArg` in declaration order.
+/// Parameters taken by `&mut` correspond to VHDL `out` or `inout`
+/// parameters: they are read before the call and written back after it with
+/// `vhpi::ForeignOut`.  A function returning `()` is registered as a
+/// procedure, any other return type as a function.  Conversion errors and
+/// `Err` results are reported through `vhpi_assert`.
+///
+/// # Example
+///
+/// ```vhdl
+/// function add_ints(a, b : integer) return integer;
+/// attribute foreign of add_ints : function is "VHPI my_lib add_ints";
+///
+/// procedure swap(a, b : inout integer);
+/// attribute foreign of swap : procedure is "VHPI my_lib swap";
+/// ```
+///
+/// ```rust,ignore
+/// #[vhpi::foreign(library = "my_lib")]
+/// fn add_ints(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// #[vhpi::foreign(library = "my_lib")]
+/// fn swap(a: &mut i32, b: &mut i32) {
+///     std::mem::swap(a, b);
+/// }
+///
+/// vhpi::startup_routines!(register_add_ints, register_swap);
+/// ```
+#[proc_macro_attribute]
+pub fn foreign(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut library: Option<LitStr> = None;
+    let mut name: Option<LitStr> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("library") {
+            library = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported foreign attribute, expected `library` or `name`"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+
+    let func = parse_macro_input!(item as ItemFn);
+
+    match expand(library, name, &func) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(
+    library: Option<LitStr>,
+    name: Option<LitStr>,
+    func: &ItemFn,
+) -> syn::Result<TokenStream2> {
+    let sig = &func.sig;
+    let Some(library) = library else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "missing `library = \"...\"` argument",
+        ));
+    };
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "foreign subprograms cannot be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "foreign subprograms cannot be generic",
+        ));
+    }
+    if let Some(variadic) = &sig.variadic {
+        return Err(syn::Error::new_spanned(
+            variadic,
+            "foreign subprograms cannot be variadic",
+        ));
+    }
+
+    let ident = &sig.ident;
+    let model = name.unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+    let exec_ident = format_ident!("__vhpi_foreign_{}", ident);
+    let register_ident = format_ident!("register_{}", ident);
+    let vis = &func.vis;
+
+    let mut reads = Vec::new();
+    let mut call_args = Vec::new();
+    let mut writes = Vec::new();
+    for (index, input) in sig.inputs.iter().enumerate() {
+        let FnArg::Typed(pat_type) = input else {
+            return Err(syn::Error::new_spanned(
+                input,
+                "foreign subprograms cannot take `self`",
+            ));
+        };
+        let index = u32::try_from(index).expect("too many parameters");
+        let local = format_ident!("arg{}", index);
+
+        match &*pat_type.ty {
+            Type::Reference(reference) if reference.mutability.is_some() => {
+                let ty = &reference.elem;
+                reads.push(quote! {
+                    let mut #local: #ty = ::vhpi::__private::read_arg(data, #index)?;
+                });
+                call_args.push(quote! { &mut #local });
+                writes.push(quote! {
+                    ::vhpi::__private::write_arg(data, #index, #local)?;
+                });
+            }
+            Type::Reference(reference) => {
+                let ty = &reference.elem;
+                reads.push(quote! {
+                    let #local: #ty = ::vhpi::__private::read_arg(data, #index)?;
+                });
+                call_args.push(quote! { &#local });
+            }
+            ty => {
+                reads.push(quote! {
+                    let #local: #ty = ::vhpi::__private::read_arg(data, #index)?;
+                });
+                call_args.push(quote! { #local });
+            }
+        }
+    }
+
+    let ret_ty = match &sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => quote! { #ty },
+    };
+
+    Ok(quote! {
+        #func
+
+        #[doc(hidden)]
+        extern "C" fn #exec_ident(data: &::vhpi::ForeignExecData) {
+            fn call(data: &::vhpi::ForeignExecData) -> ::core::result::Result<(), ::vhpi::Error> {
+                #(#reads)*
+                let ret: #ret_ty = #ident(#(#call_args),*);
+                #(#writes)*
+                ::vhpi::ForeignReturn::put(ret, data)
+            }
+
+            if let ::core::result::Result::Err(err) = call(data) {
+                ::vhpi::__private::report(data, &err);
+            }
+        }
+
+        /// Register the foreign model with the simulator.
+        #vis extern "C" fn #register_ident() {
+            let foreign_data = ::vhpi::ForeignData::new(
+                <#ret_ty as ::vhpi::ForeignReturn>::KIND,
+                #library,
+                #model,
+            )
+            .exec(#exec_ident);
+
+            if let ::core::result::Result::Err(err) = ::vhpi::register_foreignf(&foreign_data) {
+                ::vhpi::assert(
+                    ::vhpi::Severity::Error,
+                    ::std::format!("failed to register foreign model `{}`: {}", #model, err.message),
+                );
+            }
+        }
+    })
+}
+
+/// Derive `vhpi::ForeignComposite` for a struct mirroring a VHDL record.
+///
+/// Each named field is matched with the record element of the same name,
+/// ignoring case, and may be of any `ForeignComposite` type, including
+/// other derived structs and `Vec`s of them for array fields.  The derive
+/// also implements `vhpi::ForeignArg`, so the struct can be used as a
+/// foreign subprogram parameter, and through it `vhpi::ForeignOut` for
+/// `out` and `inout` parameters.
+///
+/// # Example
+///
+/// ```vhdl
+/// type header_t is record
+///     src, dst : integer;
+///     valid    : boolean;
+/// end record;
+///
+/// procedure swap_addresses(hdr : inout header_t);
+/// attribute foreign of swap_addresses : procedure is "VHPI my_lib swap_addresses";
+/// ```
+///
+/// ```rust,ignore
+/// #[derive(vhpi::ForeignComposite)]
+/// struct Header {
+///     src: i32,
+///     dst: i32,
+///     valid: bool,
+/// }
+///
+/// #[vhpi::foreign(library = "my_lib")]
+/// fn swap_addresses(hdr: &mut Header) {
+///     std::mem::swap(&mut hdr.src, &mut hdr.dst);
+/// }
+/// ```
+#[proc_macro_derive(ForeignComposite)]
+pub fn derive_foreign_composite(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    match expand_composite(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_composite(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "ForeignComposite can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &data.fields,
+            "ForeignComposite requires named fields matching the record elements",
+        ));
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let idents: Vec<_> = fields
+        .named
+        .iter()
+        .map(|field| field.ident.as_ref().expect("named field"))
+        .collect();
+    let names: Vec<_> = idents
+        .iter()
+        .map(|ident| {
+            let name = ident.to_string();
+            LitStr::new(name.strip_prefix("r#").unwrap_or(&name), ident.span())
+        })
+        .collect();
+
+    Ok(quote! {
+        impl #impl_generics ::vhpi::ForeignComposite for #ident #ty_generics #where_clause {
+            fn from_composite(value: ::vhpi::CompositeValue) -> ::core::option::Option<Self> {
+                let ::vhpi::CompositeValue::Record(mut fields) = value else {
+                    return ::core::option::Option::None;
+                };
+                ::core::option::Option::Some(Self {
+                    #(#idents: ::vhpi::__private::take_field(&mut fields, #names)?,)*
+                })
+            }
+
+            fn into_composite(self) -> ::vhpi::CompositeValue {
+                ::vhpi::CompositeValue::Record(::std::vec![
+                    #((
+                        ::std::string::String::from(#names),
+                        ::vhpi::ForeignComposite::into_composite(self.#idents),
+                    ),)*
+                ])
+            }
+        }
+
+        impl #impl_generics ::vhpi::ForeignArg for #ident #ty_generics #where_clause {
+            const FORMAT: ::vhpi::Format = ::vhpi::Format::ObjType;
+
+            fn from_value(value: ::vhpi::Value) -> ::core::option::Option<Self> {
+                ::vhpi::ForeignComposite::from_composite(::vhpi::CompositeValue::Scalar(value))
+            }
+
+            fn read(
+                data: &::vhpi::ForeignExecData,
+                index: u32,
+            ) -> ::core::result::Result<Self, ::vhpi::Error> {
+                ::vhpi::__private::read_composite(data, index)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn library() -> Option<LitStr> {
+        Some(parse_quote!("my_lib"))
+    }
+
+    fn expand_fn(func: &ItemFn) -> String {
+        expand(library(), None, func)
+            .expect("expansion failed")
+            .to_string()
+    }
+
+    fn expand_err(func: &ItemFn) -> String {
+        expand(library(), None, func)
+            .expect_err("expansion should fail")
+            .to_string()
+    }
+
+    #[test]
+    fn mut_refs_are_read_and_written_back() {
+        let out = expand_fn(&parse_quote! { fn swap(a: &mut i32, b: &i32, c: u8) {} });
+
+        assert!(out.contains("let mut arg0 : i32 = :: vhpi :: __private :: read_arg (data , 0u32)"));
+        assert!(out.contains("let arg1 : i32 = :: vhpi :: __private :: read_arg (data , 1u32)"));
+        assert!(out.contains("swap (& mut arg0 , & arg1 , arg2)"));
+        assert!(out.contains("write_arg (data , 0u32 , arg0)"));
+        assert_eq!(out.matches("write_arg").count(), 1);
+    }
+
+    #[test]
+    fn return_type_selects_model_kind() {
+        let proc = expand_fn(&parse_quote! { fn tick() {} });
+        let func = expand_fn(&parse_quote! { fn add(a: i32, b: i32) -> i32 { a + b } });
+
+        assert!(proc.contains("< () as :: vhpi :: ForeignReturn > :: KIND"));
+        assert!(func.contains("< i32 as :: vhpi :: ForeignReturn > :: KIND"));
+        assert!(func.contains("extern \"C\" fn register_add ()"));
+    }
+
+    #[test]
+    fn model_name_defaults_to_function_name() {
+        let func: ItemFn = parse_quote! { fn add(a: i32) -> i32 { a } };
+        let renamed = expand(library(), Some(parse_quote!("add_ints")), &func)
+            .expect("expansion failed")
+            .to_string();
+
+        assert!(expand_fn(&func).contains("\"my_lib\" , \"add\""));
+        assert!(renamed.contains("\"my_lib\" , \"add_ints\""));
+    }
+
+    #[test]
+    fn invalid_signatures_are_rejected() {
+        let func: ItemFn = parse_quote! { fn f() {} };
+        let missing = expand(None, None, &func).expect_err("library is required");
+        assert!(missing.to_string().contains("missing `library"));
+
+        assert!(expand_err(&parse_quote! { async fn f() {} }).contains("cannot be async"));
+        assert!(expand_err(&parse_quote! { fn f<T>(x: T) {} }).contains("cannot be generic"));
+        assert!(expand_err(&parse_quote! { fn f(&self) {} }).contains("cannot take `self`"));
+    }
+
+    #[test]
+    fn composite_fields_map_to_record_elements() {
+        let out = expand_composite(&parse_quote! {
+            struct Header { src: i32, r#type: bool }
+        })
+        .expect("expansion failed")
+        .to_string();
+
+        assert!(out.contains("take_field (& mut fields , \"src\")"));
+        assert!(out.contains("take_field (& mut fields , \"type\")"));
+        assert!(out.contains("impl :: vhpi :: ForeignArg for Header"));
+    }
+
+    #[test]
+    fn composite_requires_named_struct() {
+        let tuple = expand_composite(&parse_quote! { struct Pair(i32, i32); });
+        let enumeration = expand_composite(&parse_quote! { enum E { A } });
+
+        assert!(tuple
+            .expect_err("tuple struct")
+            .to_string()
+            .contains("named fields"));
+        assert!(enumeration
+            .expect_err("enum")
+            .to_string()
+            .contains("only be derived for structs"));
+    }
+}
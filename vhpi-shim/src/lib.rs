@@ -41,6 +41,14 @@ macro_rules! define_vhpi_forwarders {
             *mut std::ffi::c_void,
             std::ffi::c_int,
         ) -> std::ffi::c_int;
+        type VhpiScheduleTransactionFn = unsafe extern "C" fn(
+            VhpiHandleT,
+            *mut std::ffi::c_void,
+            u32,
+            *mut VhpiTimeS,
+            std::ffi::c_int,
+            *mut VhpiTimeS,
+        ) -> std::ffi::c_int;
         type VhpiGetTimeFn = unsafe extern "C" fn(*mut VhpiTimeS, *mut std::ffi::c_long);
         type VhpiGetNextTimeFn = unsafe extern "C" fn(*mut VhpiTimeS) -> std::ffi::c_int;
         type VhpiControlFn = unsafe extern "C" fn(std::ffi::c_int) -> std::ffi::c_int;
@@ -78,6 +86,8 @@ macro_rules! define_vhpi_forwarders {
         static VHPI_GET_PHYS_FN: std::sync::OnceLock<VhpiGetPhysFn> = std::sync::OnceLock::new();
         static VHPI_GET_VALUE_FN: std::sync::OnceLock<VhpiGetValueFn> = std::sync::OnceLock::new();
         static VHPI_PUT_VALUE_FN: std::sync::OnceLock<VhpiPutValueFn> = std::sync::OnceLock::new();
+        static VHPI_SCHEDULE_TRANSACTION_FN: std::sync::OnceLock<VhpiScheduleTransactionFn> =
+            std::sync::OnceLock::new();
         static VHPI_GET_TIME_FN: std::sync::OnceLock<VhpiGetTimeFn> = std::sync::OnceLock::new();
         static VHPI_GET_NEXT_TIME_FN: std::sync::OnceLock<VhpiGetNextTimeFn> =
             std::sync::OnceLock::new();
@@ -234,6 +244,24 @@ macro_rules! define_vhpi_forwarders {
             resolve_fn!(VHPI_PUT_VALUE_FN, "vhpi_put_value", VhpiPutValueFn)(object, value_p, mode)
         }
 
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn vhpi_schedule_transaction(
+            driv_hdl: VhpiHandleT,
+            value_p: *mut std::ffi::c_void,
+            num_values: u32,
+            delayp: *mut VhpiTimeS,
+            delay_mode: std::ffi::c_int,
+            pulse_rejp: *mut VhpiTimeS,
+        ) -> std::ffi::c_int {
+            resolve_fn!(
+                VHPI_SCHEDULE_TRANSACTION_FN,
+                "vhpi_schedule_transaction",
+                VhpiScheduleTransactionFn
+            )(
+                driv_hdl, value_p, num_values, delayp, delay_mode, pulse_rejp,
+            )
+        }
+
         #[unsafe(no_mangle)]
         pub unsafe extern "C" fn vhpi_get_time(
             time_p: *mut VhpiTimeS,
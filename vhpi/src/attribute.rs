@@ -0,0 +1,81 @@
+use crate::generic::enum_literal;
+use crate::{Error, Format, Handle, OneToMany, OneToOne, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Value of a user-defined attribute attached to a design object.
+pub struct Attribute {
+    /// Name of the attribute declaration, e.g. `monitor`.
+    pub name: String,
+    /// Value given in the attribute specification.
+    pub value: Value,
+    /// Enumeration literal for enum-typed attributes such as `boolean`.
+    pub literal: Option<String>,
+}
+
+impl Attribute {
+    fn read(spec: &Handle) -> Result<Self, Error> {
+        let decl = spec.handle(OneToOne::AttrDecl);
+        let name = decl
+            .get_name()
+            .or_else(|| spec.get_name())
+            .ok_or_else(|| Error::from("attribute specification has no name"))?;
+
+        // Some simulators only expose the value through the expression
+        let value = match spec.get_value(Format::ObjType) {
+            Ok(value) => value,
+            Err(err) => {
+                let expr = spec.handle(OneToOne::Expr);
+                if expr.is_null() {
+                    return Err(err);
+                }
+                expr.get_value(Format::ObjType)?
+            }
+        };
+
+        let typ = decl.handle(OneToOne::Type);
+        let literal = if typ.is_null() {
+            None
+        } else {
+            enum_literal(&typ, &value)
+        };
+
+        Ok(Self {
+            name,
+            value,
+            literal,
+        })
+    }
+}
+
+impl Handle {
+    /// Read all user-defined attributes specified for this object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value of any attribute cannot be read.
+    pub fn attributes(&self) -> Result<Vec<Attribute>, Error> {
+        self.iterator(OneToMany::AttrSpecs)
+            .map(|spec| Attribute::read(&spec))
+            .collect()
+    }
+
+    /// Look up the value of the user-defined attribute `name` on this object.
+    ///
+    /// Attribute names are compared case-insensitively.  Returns `Ok(None)`
+    /// if the attribute is not specified for this object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attribute is specified but its value cannot
+    /// be read.
+    pub fn attribute(&self, name: &str) -> Result<Option<Value>, Error> {
+        self.iterator(OneToMany::AttrSpecs)
+            .find(|spec| {
+                spec.handle(OneToOne::AttrDecl)
+                    .get_name()
+                    .is_some_and(|n| n.eq_ignore_ascii_case(name))
+            })
+            .map(|spec| Attribute::read(&spec).map(|attr| attr.value))
+            .transpose()
+    }
+}
@@ -0,0 +1,139 @@
+//! Reading and writing many objects in one call.
+//!
+//! [`read_many`] and [`write_many`] share their transfer buffers across all
+//! the objects in the batch, so sampling a register file or memory array
+//! costs one buffer rather than one per element.  [`snapshot_many`] defers
+//! the read to the end of the current time step, where no further deltas
+//! can run and every value belongs to the same settled state.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{Format, Handle, OneToMany};
+//!
+//! fn sample(regs: &Handle) -> Result<(), vhpi::Error> {
+//!     let handles: Vec<Handle> = regs.iterator(OneToMany::IndexedNames).collect();
+//!     vhpi::snapshot_many(handles, Format::LogicVec, |values| {
+//!         for (index, value) in values.iter().enumerate() {
+//!             if let Ok(value) = value {
+//!                 vhpi::printf!("reg[{index}] = {value}");
+//!             }
+//!         }
+//!     })
+//!     .expect("failed to register snapshot callback");
+//!     Ok(())
+//! }
+//! ```
+
+use crate::reader::bind_buffer;
+use crate::{
+    register_cb, CbReason, Error, Format, Handle, PutValueMode, RegisterCbError, Value, ValueReader,
+};
+
+/// Reads every handle in `handles` using `format`.
+///
+/// One [`ValueReader`] is shared by the whole batch, so objects of the same
+/// size reuse its buffers and skip the sizing pass after the first.  With
+/// [`Format::ObjType`] each object's native format is queried in turn.
+///
+/// All values are read within the current callback, so they reflect the
+/// same delta cycle.  Use [`snapshot_many`] to read them once the time step
+/// has settled.
+#[must_use]
+pub fn read_many(handles: &[Handle], format: Format) -> Vec<Result<Value, Error>> {
+    let mut reader = ValueReader::with_format(format);
+    handles
+        .iter()
+        .map(|handle| {
+            if format == Format::ObjType {
+                reader.refresh(handle)?;
+            }
+            reader.read(handle)
+        })
+        .collect()
+}
+
+/// Reads every handle in `handles` at the end of the current time step and
+/// passes the results to `callback`.
+///
+/// The read runs in a single `EndOfTimeStep` callback, a read-only phase in
+/// which no more deltas are scheduled, so the results form a consistent
+/// snapshot.  The callback fires once.
+///
+/// # Errors
+///
+/// Returns [`RegisterCbError::Error`] when the simulator reports an error while
+/// registering the callback.
+pub fn snapshot_many<F>(
+    handles: Vec<Handle>,
+    format: Format,
+    callback: F,
+) -> Result<Handle, RegisterCbError>
+where
+    F: Fn(Vec<Result<Value, Error>>) + 'static,
+{
+    register_cb(CbReason::EndOfTimeStep, move |_| {
+        callback(read_many(&handles, format));
+    })
+}
+
+/// Writes each value to its paired handle using `mode`.
+///
+/// Logic vectors are encoded into one buffer shared by the whole batch and
+/// other vector values are passed to the simulator without copying.
+/// Scalars and strings are written with [`Handle::put_value`].  The result
+/// for each pair is returned in order; a failed write does not stop the
+/// rest of the batch.
+pub fn write_many<'a>(
+    writes: impl IntoIterator<Item = (&'a Handle, Value)>,
+    mode: PutValueMode,
+) -> Vec<Result<(), Error>> {
+    let mut enums: Vec<vhpi_sys::vhpiEnumT> = Vec::new();
+    writes
+        .into_iter()
+        .map(|(handle, value)| match value {
+            Value::LogicVec(vec) => {
+                enums.clear();
+                enums.extend(vec.iter().map(|&val| vhpi_sys::vhpiEnumT::from(val)));
+                put_buffer(handle, Format::LogicVec, &mut enums, mode.clone())
+            }
+            Value::EnumVec(mut vec) => put_buffer(handle, Format::EnumVec, &mut vec, mode.clone()),
+            Value::SmallEnumVec(mut vec) => {
+                put_buffer(handle, Format::SmallEnumVec, &mut vec, mode.clone())
+            }
+            Value::IntVec(mut vec) => put_buffer(handle, Format::IntVec, &mut vec, mode.clone()),
+            Value::LongIntVec(mut vec) => {
+                put_buffer(handle, Format::LongIntVec, &mut vec, mode.clone())
+            }
+            Value::RealVec(mut vec) => put_buffer(handle, Format::RealVec, &mut vec, mode.clone()),
+            value => handle.put_value(value, mode.clone()),
+        })
+        .collect()
+}
+
+fn put_buffer<T>(
+    handle: &Handle,
+    format: Format,
+    buf: &mut [T],
+    mode: PutValueMode,
+) -> Result<(), Error> {
+    let mut val = vhpi_sys::vhpiValueT {
+        format: format.into(),
+        bufSize: size_of_val(buf),
+        numElems: buf
+            .len()
+            .try_into()
+            .map_err(|_| Error::from("vector is too long for VHPI"))?,
+        unit: vhpi_sys::vhpiPhysS { high: 0, low: 0 },
+        value: vhpi_sys::vhpiValueS__bindgen_ty_1 { longintg: 0 },
+    };
+    bind_buffer(&mut val, buf.as_mut_ptr().cast());
+
+    let rc = unsafe { vhpi_sys::vhpi_put_value(handle.as_raw(), &raw mut val, mode.into()) };
+
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(crate::check_error().unwrap_or_else(|| "Unknown error in vhpi_put_value".into()))
+    }
+}
@@ -0,0 +1,191 @@
+//! Inspecting the VHDL call of a foreign subprogram via
+//! [`ForeignExecData::call_info`].
+//!
+//! When a foreign function is given arguments it cannot handle, the most
+//! useful diagnostic names the call site in the VHDL source rather than the
+//! Rust function.  [`ForeignCallInfo`] gathers the subprogram, its formal
+//! parameters and the calling process or subprogram for that purpose.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{ForeignExecData, Severity};
+//!
+//! unsafe extern "C" fn checked_exec(data: &ForeignExecData) {
+//!     if data.arg("width").is_none() {
+//!         vhpi::assert(Severity::Error, format!("{}: missing width", data.call_info()));
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::{
+    handle, ClassKind, ForeignExecData, Handle, Mode, OneToMany, OneToOne, SourceLocation,
+    StackFrame, StrProperty,
+};
+
+/// A formal parameter of a foreign subprogram.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignParamInfo {
+    /// Parameter name as reported by the simulator.
+    pub name: String,
+    /// Parameter mode, `None` if the simulator reports an unknown mode.
+    pub mode: Option<Mode>,
+    /// Object class of the parameter, e.g. [`ClassKind::VarParamDecl`].
+    pub class: Option<ClassKind>,
+    /// Name of the parameter type.
+    pub type_name: Option<String>,
+    /// Whether the declaration has a default value, so the actual may have
+    /// been omitted by the caller.
+    pub has_default: bool,
+}
+
+/// Description of the call that invoked a foreign subprogram.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignCallInfo {
+    /// Name of the called subprogram.
+    pub subprogram: String,
+    /// Formal parameters in declaration order.
+    pub params: Vec<ForeignParamInfo>,
+    /// Full name of the calling subprogram, process or statement.
+    pub caller: Option<String>,
+    /// Location of the call in the VHDL source.
+    ///
+    /// Taken from the call itself when the simulator reports it, otherwise
+    /// from the statement running in the calling subprogram's frame, which
+    /// is the call.  `None` when the foreign subprogram was called directly
+    /// from a process or concurrent statement and the simulator does not
+    /// report the call, since the process itself only knows where it is
+    /// declared.
+    pub location: Option<SourceLocation>,
+}
+
+impl ForeignCallInfo {
+    #[must_use]
+    /// Find a formal parameter by name, ignoring case.
+    pub fn param(&self, name: &str) -> Option<&ForeignParamInfo> {
+        self.params
+            .iter()
+            .find(|param| param.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for ForeignCallInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{location}: ")?;
+        }
+        write!(f, "call to {}", self.subprogram)?;
+        if let Some(caller) = &self.caller {
+            write!(f, " from {caller}")?;
+        }
+        Ok(())
+    }
+}
+
+fn is_call_kind(kind: Option<ClassKind>) -> bool {
+    matches!(kind, Some(ClassKind::FuncCall | ClassKind::SeqProcCallStmt))
+}
+
+fn param_info(param: &Handle) -> ForeignParamInfo {
+    ForeignParamInfo {
+        name: param.get_name().unwrap_or_default(),
+        mode: param.get_mode(),
+        class: param.get_kind(),
+        type_name: param.handle(OneToOne::Type).get_name(),
+        has_default: !param.handle(OneToOne::InitExpr).is_null(),
+    }
+}
+
+fn subprogram_name(obj: &Handle) -> String {
+    let body = obj.handle(OneToOne::SubpBody);
+    let subp = if is_call_kind(obj.get_kind()) && !body.is_null() {
+        &body
+    } else {
+        obj
+    };
+    subp.get_name()
+        .or_else(|| subp.get_str(StrProperty::LabelName))
+        .unwrap_or_default()
+}
+
+fn full_name(handle: &Handle) -> Option<String> {
+    handle.get_full_name().or_else(|| handle.get_name())
+}
+
+impl ForeignExecData {
+    #[must_use]
+    /// Describe the VHDL call that invoked this foreign subprogram.
+    ///
+    /// The caller is the subprogram one level up the call stack, or the
+    /// process or statement currently running when the foreign subprogram
+    /// was called directly from it.  Fields the simulator does not report
+    /// are left empty.
+    pub fn call_info(&self) -> ForeignCallInfo {
+        let obj = self.obj();
+        let params = obj
+            .iterator(OneToMany::ParamDecls)
+            .map(|param| param_info(&param))
+            .collect();
+
+        let caller_frame = StackFrame::current().and_then(|frame| frame.up());
+        let location = if is_call_kind(obj.get_kind()) {
+            obj.source_location()
+        } else {
+            None
+        }
+        .or_else(|| caller_frame.as_ref().and_then(StackFrame::location));
+        let caller =
+            caller_frame.map_or_else(|| handle(OneToOne::CurRegion), StackFrame::into_handle);
+
+        ForeignCallInfo {
+            subprogram: subprogram_name(&obj),
+            params,
+            caller: full_name(&caller),
+            location,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(caller: Option<&str>, location: Option<SourceLocation>) -> ForeignCallInfo {
+        ForeignCallInfo {
+            subprogram: "DIV_MOD".to_string(),
+            params: vec![ForeignParamInfo {
+                name: "A".to_string(),
+                mode: Some(Mode::In),
+                class: Some(ClassKind::ConstParamDecl),
+                type_name: Some("INTEGER".to_string()),
+                has_default: false,
+            }],
+            caller: caller.map(str::to_string),
+            location,
+        }
+    }
+
+    #[test]
+    fn describes_call_site() {
+        let location = SourceLocation {
+            file: "tb.vhdl".to_string(),
+            begin_line: 42,
+            end_line: 42,
+            design_unit: None,
+        };
+        assert_eq!(
+            info(Some(":TB:P"), Some(location)).to_string(),
+            "tb.vhdl:42: call to DIV_MOD from :TB:P"
+        );
+        assert_eq!(info(None, None).to_string(), "call to DIV_MOD");
+    }
+
+    #[test]
+    fn finds_params_ignoring_case() {
+        let info = info(None, None);
+        assert_eq!(info.param("a").and_then(|p| p.mode.clone()), Some(Mode::In));
+        assert!(info.param("b").is_none());
+    }
+}
@@ -0,0 +1,388 @@
+use crate::{
+    ClassKind, Error, Format, Handle, LogicVal, LogicVec, OneToMany, OneToOne, PutValueMode, Time,
+    Value,
+};
+
+/// Value of an object of any type, including records and arrays of records.
+///
+/// A single [`Format`] cannot describe a record, so composite objects are
+/// read element by element through `vhpiSelectedNames` and
+/// `vhpiIndexedNames`.  Arrays of scalars, such as `std_logic_vector` or
+/// `string`, are kept as one [`Value`] in a [`CompositeValue::Scalar`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompositeValue {
+    /// A scalar or an array of scalars.
+    Scalar(Value),
+    /// Record fields in declaration order.
+    Record(Vec<(String, CompositeValue)>),
+    /// Elements of an array of composite elements, in index order.
+    Array(Vec<CompositeValue>),
+}
+
+impl CompositeValue {
+    #[must_use]
+    /// Return the record field `name`, ignoring case.
+    ///
+    /// Returns `None` for scalars, arrays and records without that field.
+    pub fn field(&self, name: &str) -> Option<&CompositeValue> {
+        match self {
+            CompositeValue::Record(fields) => fields
+                .iter()
+                .find(|(field, _)| field.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Return the array element at position `index`, counting from the left
+    /// bound.
+    pub fn element(&self, index: usize) -> Option<&CompositeValue> {
+        match self {
+            CompositeValue::Array(elements) => elements.get(index),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Return the value of a scalar.
+    pub fn as_scalar(&self) -> Option<&Value> {
+        match self {
+            CompositeValue::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl From<Value> for CompositeValue {
+    fn from(value: Value) -> Self {
+        CompositeValue::Scalar(value)
+    }
+}
+
+/// Conversion between a Rust type and a [`CompositeValue`].
+///
+/// Implemented for the scalar types supported by
+/// [`ForeignArg`](crate::ForeignArg), for `Vec<T>` and for structs using
+/// `#[derive(ForeignComposite)]` with the `macros` feature.  Derived structs
+/// map each field to the record element with the same name, ignoring case,
+/// and can be used directly as foreign subprogram parameters.
+pub trait ForeignComposite: Sized {
+    /// Convert from a value read from the simulator, or return `None` if the
+    /// shape or kind does not match.
+    fn from_composite(value: CompositeValue) -> Option<Self>;
+
+    /// Convert to a value that can be written back to the simulator.
+    fn into_composite(self) -> CompositeValue;
+}
+
+macro_rules! impl_foreign_composite {
+    ($ty:ty, $variant:ident) => {
+        impl ForeignComposite for $ty {
+            fn from_composite(value: CompositeValue) -> Option<Self> {
+                match value {
+                    CompositeValue::Scalar(Value::$variant(v)) => Some(v),
+                    _ => None,
+                }
+            }
+
+            fn into_composite(self) -> CompositeValue {
+                CompositeValue::Scalar(Value::$variant(self))
+            }
+        }
+    };
+}
+
+impl_foreign_composite!(i32, Int);
+impl_foreign_composite!(i64, LongInt);
+impl_foreign_composite!(f64, Real);
+impl_foreign_composite!(char, Char);
+impl_foreign_composite!(LogicVal, Logic);
+impl_foreign_composite!(LogicVec, LogicVec);
+impl_foreign_composite!(String, Str);
+impl_foreign_composite!(Time, Time);
+
+impl ForeignComposite for bool {
+    fn from_composite(value: CompositeValue) -> Option<Self> {
+        match value {
+            CompositeValue::Scalar(Value::Enum(n)) => Some(n != 0),
+            CompositeValue::Scalar(Value::SmallEnum(n)) => Some(n != 0),
+            _ => None,
+        }
+    }
+
+    fn into_composite(self) -> CompositeValue {
+        CompositeValue::Scalar(Value::Enum(u32::from(self)))
+    }
+}
+
+impl ForeignComposite for CompositeValue {
+    fn from_composite(value: CompositeValue) -> Option<Self> {
+        Some(value)
+    }
+
+    fn into_composite(self) -> CompositeValue {
+        self
+    }
+}
+
+/// Split an array of scalars read as a single vector value into its
+/// elements.
+fn split_vector(value: Value) -> Option<Vec<CompositeValue>> {
+    let elements: Vec<Value> = match value {
+        Value::IntVec(v) => v.into_iter().map(Value::Int).collect(),
+        Value::LongIntVec(v) => v.into_iter().map(Value::LongInt).collect(),
+        Value::RealVec(v) => v.into_iter().map(Value::Real).collect(),
+        Value::EnumVec(v) => v.into_iter().map(Value::Enum).collect(),
+        Value::SmallEnumVec(v) => v.into_iter().map(Value::SmallEnum).collect(),
+        Value::TimeVec(v) => v.into_iter().map(Value::Time).collect(),
+        Value::LogicVec(v) => v.iter().map(|bit| Value::Logic(*bit)).collect(),
+        Value::Str(s) => s.chars().map(Value::Char).collect(),
+        _ => return None,
+    };
+    Some(elements.into_iter().map(CompositeValue::Scalar).collect())
+}
+
+/// Join scalar elements of the same kind back into a single vector value so
+/// that they can be written with one call, the reverse of [`split_vector`].
+fn pack_vector(elements: Vec<CompositeValue>) -> CompositeValue {
+    macro_rules! pack {
+        ($variant:ident) => {
+            elements
+                .iter()
+                .map(|element| match element {
+                    CompositeValue::Scalar(Value::$variant(v)) => Some(v.clone()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+        };
+    }
+
+    let packed = match elements.first() {
+        Some(CompositeValue::Scalar(Value::Int(_))) => pack!(Int).map(Value::IntVec),
+        Some(CompositeValue::Scalar(Value::LongInt(_))) => pack!(LongInt).map(Value::LongIntVec),
+        Some(CompositeValue::Scalar(Value::Real(_))) => pack!(Real).map(Value::RealVec),
+        Some(CompositeValue::Scalar(Value::Enum(_))) => pack!(Enum).map(Value::EnumVec),
+        Some(CompositeValue::Scalar(Value::Time(_))) => pack!(Time).map(Value::TimeVec),
+        Some(CompositeValue::Scalar(Value::Logic(_))) => {
+            pack!(Logic).map(|bits| Value::LogicVec(LogicVec::new(bits)))
+        }
+        Some(CompositeValue::Scalar(Value::Char(_))) => {
+            pack!(Char).map(|chars| Value::Str(chars.into_iter().collect()))
+        }
+        _ => None,
+    };
+    match packed {
+        Some(value) => CompositeValue::Scalar(value),
+        None => CompositeValue::Array(elements),
+    }
+}
+
+impl<T: ForeignComposite> ForeignComposite for Vec<T> {
+    fn from_composite(value: CompositeValue) -> Option<Self> {
+        let elements = match value {
+            CompositeValue::Array(elements) => elements,
+            CompositeValue::Scalar(value) => split_vector(value)?,
+            CompositeValue::Record(_) => return None,
+        };
+        elements.into_iter().map(T::from_composite).collect()
+    }
+
+    fn into_composite(self) -> CompositeValue {
+        pack_vector(self.into_iter().map(T::into_composite).collect())
+    }
+}
+
+/// Resolve a subtype to its base type.
+fn base_type(typ: Handle) -> Handle {
+    if typ.get_kind() == Some(ClassKind::SubtypeDecl) {
+        let base = typ.handle(OneToOne::BaseType);
+        if !base.is_null() {
+            return base;
+        }
+    }
+    typ
+}
+
+/// Return `true` for records and arrays with composite elements.
+fn is_composite_type(typ: &Handle) -> bool {
+    match typ.get_kind() {
+        Some(ClassKind::RecordTypeDecl) => true,
+        Some(ClassKind::ArrayTypeDecl) => {
+            let elem = base_type(typ.handle(OneToOne::ElemType));
+            matches!(
+                elem.get_kind(),
+                Some(ClassKind::RecordTypeDecl | ClassKind::ArrayTypeDecl)
+            )
+        }
+        _ => false,
+    }
+}
+
+fn record_field_names(typ: &Handle) -> Vec<String> {
+    typ.iterator(OneToMany::RecordElems)
+        .map(|elem| elem.get_name().unwrap_or_default())
+        .collect()
+}
+
+/// Write `value` to `obj` element by element, calling `put` for every
+/// scalar or array of scalars.
+pub(crate) fn put_composite_with(
+    obj: &Handle,
+    value: CompositeValue,
+    put: &mut dyn FnMut(&Handle, Value) -> Result<(), Error>,
+) -> Result<(), Error> {
+    match value {
+        CompositeValue::Scalar(value) => put(obj, value),
+        CompositeValue::Record(fields) => {
+            let names = record_field_names(&base_type(obj.handle(OneToOne::Type)));
+            let elements: Vec<Handle> = obj.iterator(OneToMany::SelectedNames).collect();
+            for (field, value) in fields {
+                let position = names
+                    .iter()
+                    .position(|name| name.eq_ignore_ascii_case(&field))
+                    .ok_or_else(|| {
+                        Error::from(format!("no record field named {field}").as_str())
+                    })?;
+                let element = elements.get(position).ok_or_else(|| {
+                    Error::from(format!("cannot select record field {field}").as_str())
+                })?;
+                put_composite_with(element, value, put)?;
+            }
+            Ok(())
+        }
+        CompositeValue::Array(elements) => {
+            for (index, value) in elements.into_iter().enumerate() {
+                let element = i32::try_from(index)
+                    .ok()
+                    .and_then(|index| obj.handle_by_index(OneToMany::IndexedNames, index))
+                    .ok_or_else(|| {
+                        Error::from(format!("array index {index} is out of range").as_str())
+                    })?;
+                put_composite_with(&element, value, put)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+impl Handle {
+    /// Read the value of an object of any type.
+    ///
+    /// Records are read field by field and arrays of records or arrays
+    /// element by element.  Everything else is read with
+    /// [`Format::ObjType`] into a [`CompositeValue::Scalar`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value of any element cannot be read.
+    pub fn get_composite_value(&self) -> Result<CompositeValue, Error> {
+        let typ = base_type(self.handle(OneToOne::Type));
+        if !is_composite_type(&typ) {
+            return self.get_value(Format::ObjType).map(CompositeValue::Scalar);
+        }
+
+        if typ.get_kind() == Some(ClassKind::RecordTypeDecl) {
+            let names = record_field_names(&typ);
+            let fields = self
+                .iterator(OneToMany::SelectedNames)
+                .enumerate()
+                .map(|(position, element)| {
+                    let name = names
+                        .get(position)
+                        .cloned()
+                        .or_else(|| element.get_name())
+                        .unwrap_or_default();
+                    Ok((name, element.get_composite_value()?))
+                })
+                .collect::<Result<_, Error>>()?;
+            Ok(CompositeValue::Record(fields))
+        } else {
+            let elements = self
+                .iterator(OneToMany::IndexedNames)
+                .map(|element| element.get_composite_value())
+                .collect::<Result<_, Error>>()?;
+            Ok(CompositeValue::Array(elements))
+        }
+    }
+
+    /// Write a value to an object of any type.
+    ///
+    /// Record fields are matched by name, ignoring case, and only the fields
+    /// present in `value` are written.  Array elements are written from the
+    /// left bound.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a field or index does not exist, or the
+    /// simulator rejects any of the writes.
+    pub fn put_composite_value(
+        &self,
+        value: CompositeValue,
+        mode: PutValueMode,
+    ) -> Result<(), Error> {
+        put_composite_with(self, value, &mut |obj, value| {
+            obj.put_value(value, mode.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors_convert_from_arrays_and_scalar_vectors() {
+        let array = CompositeValue::Array(vec![
+            CompositeValue::Scalar(Value::Int(1)),
+            CompositeValue::Scalar(Value::Int(2)),
+        ]);
+        assert_eq!(Vec::<i32>::from_composite(array), Some(vec![1, 2]));
+        assert_eq!(
+            Vec::<i32>::from_composite(CompositeValue::Scalar(Value::IntVec(vec![3, 4]))),
+            Some(vec![3, 4])
+        );
+        assert_eq!(
+            Vec::<char>::from_composite(CompositeValue::Scalar(Value::Str("ab".into()))),
+            Some(vec!['a', 'b'])
+        );
+        assert_eq!(
+            Vec::<i32>::from_composite(CompositeValue::Scalar(Value::Int(1))),
+            None
+        );
+        assert_eq!(
+            vec![true, false].into_composite(),
+            CompositeValue::Scalar(Value::EnumVec(vec![1, 0]))
+        );
+        assert_eq!(
+            vec!['h', 'i'].into_composite(),
+            CompositeValue::Scalar(Value::Str("hi".into()))
+        );
+        let nested = vec![vec![1, 2], vec![3]].into_composite();
+        assert_eq!(
+            nested,
+            CompositeValue::Array(vec![
+                CompositeValue::Scalar(Value::IntVec(vec![1, 2])),
+                CompositeValue::Scalar(Value::IntVec(vec![3])),
+            ])
+        );
+        assert_eq!(
+            Vec::<Vec<i32>>::from_composite(nested),
+            Some(vec![vec![1, 2], vec![3]])
+        );
+    }
+
+    #[test]
+    fn record_fields_are_found_ignoring_case() {
+        let header = CompositeValue::Record(vec![
+            ("SRC".into(), CompositeValue::Scalar(Value::Int(10))),
+            ("DST".into(), CompositeValue::Scalar(Value::Int(20))),
+        ]);
+        assert_eq!(
+            header.field("dst").and_then(CompositeValue::as_scalar),
+            Some(&Value::Int(20))
+        );
+        assert_eq!(header.field("len"), None);
+        assert_eq!(header.element(0), None);
+    }
+}
@@ -0,0 +1,516 @@
+//! Signal-level connectivity graph built from VHPI driver and load relationships.
+//!
+//! [`ConnectivityGraph::build`] walks the design hierarchy below a region and
+//! records one node per signal, port and process.  Edges follow the direction
+//! of data flow and are derived from these one-to-many relationships:
+//!
+//! | Relationship                         | Edge                              |
+//! |--------------------------------------|-----------------------------------|
+//! | `Drivers` of a signal                | [`EdgeKind::Drive`] process → signal |
+//! | `LocalContributors` of a signal      | [`EdgeKind::Drive`] contributor → signal |
+//! | `BasicSignals` of a port or signal   | [`EdgeKind::Drive`] basic signal → object |
+//! | `LocalLoads`/`OptimizedLoads`        | [`EdgeKind::Load`] signal → load  |
+//! | `Sensitivities` of a process         | [`EdgeKind::Load`] signal → process |
+//!
+//! Simulators are not required to implement every relationship, so a missing
+//! edge means "not reported" rather than "not connected".
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{ConnectivityGraph, OneToOne};
+//!
+//! let root = vhpi::handle(OneToOne::RootInst);
+//! let graph = ConnectivityGraph::build(&root);
+//!
+//! if let Some(clk) = graph.find(":tb:clk") {
+//!     for id in graph.sensitive_processes(clk) {
+//!         vhpi::printf!("{} is sensitive to clk", graph.node(id).name());
+//!     }
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::process::is_process_kind;
+use crate::{ClassKind, Handle, OneToMany, OneToOne};
+
+/// Index of a node inside a [`ConnectivityGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// Category of a connectivity graph node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// Signal declaration or a sub-element of one.
+    Signal,
+    /// Port declaration.
+    Port,
+    /// Process statement or equivalent process of a concurrent statement.
+    Process,
+    /// Driver whose owning process could not be determined.
+    Driver,
+    /// Any other object reported by the simulator.
+    Other,
+}
+
+impl NodeKind {
+    fn from_class(kind: Option<ClassKind>) -> Self {
+        match kind {
+            Some(ClassKind::SigDecl | ClassKind::SelectedName | ClassKind::IndexedName) => {
+                NodeKind::Signal
+            }
+            Some(ClassKind::PortDecl | ClassKind::InPort | ClassKind::OutPort) => NodeKind::Port,
+            Some(kind) if is_process_kind(&kind) => NodeKind::Process,
+            Some(ClassKind::Driver) => NodeKind::Driver,
+            _ => NodeKind::Other,
+        }
+    }
+}
+
+/// Relationship a connectivity edge was derived from.
+///
+/// Edges always point in the direction of data flow: from the object that
+/// produces a value to the object that consumes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// The source contributes a value to the target.
+    Drive,
+    /// The target reads the value of the source.
+    Load,
+}
+
+/// A signal, port or process in a [`ConnectivityGraph`].
+#[derive(Debug, Clone)]
+pub struct Node {
+    name: String,
+    kind: NodeKind,
+}
+
+impl Node {
+    #[must_use]
+    /// Hierarchical name of the object, in lower case.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    /// Category of the object.
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+}
+
+/// A directed edge between two nodes of a [`ConnectivityGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    /// Node producing the value.
+    pub from: NodeId,
+    /// Node consuming the value.
+    pub to: NodeId,
+    /// Relationship the edge was derived from.
+    pub kind: EdgeKind,
+}
+
+/// Driver/load graph over the signals, ports and processes of a design.
+#[derive(Debug, Default)]
+pub struct ConnectivityGraph {
+    topology: Topology,
+    handles: Vec<Handle>,
+    unnamed: usize,
+}
+
+impl ConnectivityGraph {
+    #[must_use]
+    /// Build the graph for `root` and every region nested below it.
+    pub fn build(root: &Handle) -> Self {
+        let mut graph = Self::default();
+        graph.add_region(root);
+        graph
+    }
+
+    fn add_region(&mut self, region: &Handle) {
+        for port in region.iterator(OneToMany::PortDecls) {
+            self.add_net(port);
+        }
+
+        for sig in region.iterator(OneToMany::SigDecls) {
+            self.add_net(sig);
+        }
+
+        for stmt in region.iterator(OneToMany::Stmts) {
+            if NodeKind::from_class(stmt.get_kind()) == NodeKind::Process {
+                self.add_process(stmt);
+            }
+        }
+
+        for stmt in region.iterator(OneToMany::EqProcessStmts) {
+            self.add_process(stmt);
+        }
+
+        for sub in region.iterator(OneToMany::InternalRegions) {
+            self.add_region(&sub);
+        }
+    }
+
+    fn add_net(&mut self, net: Handle) {
+        let id = self.intern(net);
+
+        for driver in self.handle(id).iterator(OneToMany::Drivers) {
+            // Attribute the driver to its process when the simulator reports one
+            let process = driver.handle(OneToOne::Parent);
+            let source = if !process.is_null()
+                && NodeKind::from_class(process.get_kind()) == NodeKind::Process
+            {
+                self.intern(process)
+            } else {
+                self.intern(driver)
+            };
+            self.topology.add_edge(source, id, EdgeKind::Drive);
+        }
+
+        for contributor in self.handle(id).iterator(OneToMany::LocalContributors) {
+            let source = self.intern(contributor);
+            self.topology.add_edge(source, id, EdgeKind::Drive);
+        }
+
+        for basic in self.handle(id).iterator(OneToMany::BasicSignals) {
+            let source = self.intern(basic);
+            if source != id {
+                self.topology.add_edge(source, id, EdgeKind::Drive);
+            }
+        }
+
+        for relation in [OneToMany::LocalLoads, OneToMany::OptimizedLoads] {
+            for load in self.handle(id).iterator(relation) {
+                let target = self.intern(load);
+                self.topology.add_edge(id, target, EdgeKind::Load);
+            }
+        }
+    }
+
+    fn add_process(&mut self, process: Handle) {
+        let id = self.intern(process);
+
+        for sig in self.handle(id).iterator(OneToMany::Sensitivities) {
+            let source = self.intern(sig);
+            self.topology.add_edge(source, id, EdgeKind::Load);
+        }
+    }
+
+    /// Look up or create the node for `handle`.
+    ///
+    /// Nodes are keyed by handle identity.  The name only narrows the
+    /// search, so distinct objects that report the same name, or no name at
+    /// all, still get nodes of their own.
+    fn intern(&mut self, handle: Handle) -> NodeId {
+        let name = handle.get_full_name().or_else(|| handle.get_name());
+        let key = name.as_deref().unwrap_or_default();
+        if let Some(&id) = self
+            .topology
+            .named(key)
+            .iter()
+            .find(|id| self.handles[id.0] == handle)
+        {
+            return id;
+        }
+
+        if name.is_none() {
+            self.unnamed += 1;
+        }
+        let id = self
+            .topology
+            .add_node(key, NodeKind::from_class(handle.get_kind()));
+        self.handles.push(handle);
+        id
+    }
+
+    #[must_use]
+    /// Number of nodes whose object reported no name.
+    ///
+    /// These nodes have an empty [`Node::name`] and cannot be found with
+    /// [`ConnectivityGraph::find`].
+    pub fn unnamed(&self) -> usize {
+        self.unnamed
+    }
+
+    #[must_use]
+    /// Number of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.topology.nodes.len()
+    }
+
+    #[must_use]
+    /// Return `true` if the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.topology.nodes.is_empty()
+    }
+
+    #[must_use]
+    /// Return the node with identifier `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` does not belong to this graph.
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.topology.nodes[id.0]
+    }
+
+    #[must_use]
+    /// Return the handle of the simulator object behind node `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` does not belong to this graph.
+    pub fn handle(&self, id: NodeId) -> &Handle {
+        &self.handles[id.0]
+    }
+
+    /// Iterate over all node identifiers.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.len()).map(NodeId)
+    }
+
+    #[must_use]
+    /// Return all edges in insertion order.
+    pub fn edges(&self) -> &[Edge] {
+        &self.topology.edges
+    }
+
+    #[must_use]
+    /// Find a node by hierarchical name.
+    ///
+    /// VHDL names are case-insensitive, so the lookup is as well.  When
+    /// several objects report the same name, the first one added is
+    /// returned.
+    pub fn find(&self, full_name: &str) -> Option<NodeId> {
+        self.topology.find(full_name)
+    }
+
+    #[must_use]
+    /// Nodes that drive `id`: processes, contributors and basic signals.
+    pub fn drivers(&self, id: NodeId) -> Vec<NodeId> {
+        self.topology.sources(id, EdgeKind::Drive)
+    }
+
+    #[must_use]
+    /// Nodes driven by `id`.
+    pub fn driven(&self, id: NodeId) -> Vec<NodeId> {
+        self.topology.targets(id, EdgeKind::Drive)
+    }
+
+    #[must_use]
+    /// Nodes that read the value of `id`.
+    pub fn loads(&self, id: NodeId) -> Vec<NodeId> {
+        self.topology.targets(id, EdgeKind::Load)
+    }
+
+    #[must_use]
+    /// Processes that have `id` in their sensitivity list or load it.
+    pub fn sensitive_processes(&self, id: NodeId) -> Vec<NodeId> {
+        self.topology.sensitive_processes(id)
+    }
+
+    #[must_use]
+    /// Transitive fan-in cone of `id`, nearest nodes first.
+    ///
+    /// Follows edges backwards for at most `max_depth` hops, or without limit
+    /// when `max_depth` is `None`.  The starting node is not included.
+    pub fn fan_in(&self, id: NodeId, max_depth: Option<usize>) -> Vec<NodeId> {
+        self.topology.fan_in(id, max_depth)
+    }
+
+    #[must_use]
+    /// Transitive fan-out cone of `id`, nearest nodes first.
+    ///
+    /// Follows edges forwards for at most `max_depth` hops, or without limit
+    /// when `max_depth` is `None`.  The starting node is not included.
+    pub fn fan_out(&self, id: NodeId, max_depth: Option<usize>) -> Vec<NodeId> {
+        self.topology.fan_out(id, max_depth)
+    }
+}
+
+/// Nodes and edges of a [`ConnectivityGraph`] without the simulator handles.
+#[derive(Debug, Default)]
+struct Topology {
+    nodes: Vec<Node>,
+    by_name: HashMap<String, Vec<NodeId>>,
+    edges: Vec<Edge>,
+    edge_set: HashSet<Edge>,
+    incoming: Vec<Vec<usize>>,
+    outgoing: Vec<Vec<usize>>,
+}
+
+impl Topology {
+    fn add_node(&mut self, name: &str, kind: NodeKind) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        let name = name.to_ascii_lowercase();
+        self.by_name.entry(name.clone()).or_default().push(id);
+        self.nodes.push(Node { name, kind });
+        self.incoming.push(Vec::new());
+        self.outgoing.push(Vec::new());
+        id
+    }
+
+    fn add_edge(&mut self, from: NodeId, to: NodeId, kind: EdgeKind) {
+        let edge = Edge { from, to, kind };
+        if !self.edge_set.insert(edge) {
+            return;
+        }
+
+        let index = self.edges.len();
+        self.edges.push(edge);
+        self.outgoing[from.0].push(index);
+        self.incoming[to.0].push(index);
+    }
+
+    fn named(&self, name: &str) -> &[NodeId] {
+        self.by_name
+            .get(&name.to_ascii_lowercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    fn find(&self, name: &str) -> Option<NodeId> {
+        self.named(name).first().copied()
+    }
+
+    fn sources(&self, id: NodeId, kind: EdgeKind) -> Vec<NodeId> {
+        self.incoming[id.0]
+            .iter()
+            .map(|&e| self.edges[e])
+            .filter(|e| e.kind == kind)
+            .map(|e| e.from)
+            .collect()
+    }
+
+    fn targets(&self, id: NodeId, kind: EdgeKind) -> Vec<NodeId> {
+        self.outgoing[id.0]
+            .iter()
+            .map(|&e| self.edges[e])
+            .filter(|e| e.kind == kind)
+            .map(|e| e.to)
+            .collect()
+    }
+
+    fn sensitive_processes(&self, id: NodeId) -> Vec<NodeId> {
+        self.targets(id, EdgeKind::Load)
+            .into_iter()
+            .filter(|&n| self.nodes[n.0].kind == NodeKind::Process)
+            .collect()
+    }
+
+    fn fan_in(&self, id: NodeId, max_depth: Option<usize>) -> Vec<NodeId> {
+        self.cone(id, max_depth, |n| &self.incoming[n.0], |e| e.from)
+    }
+
+    fn fan_out(&self, id: NodeId, max_depth: Option<usize>) -> Vec<NodeId> {
+        self.cone(id, max_depth, |n| &self.outgoing[n.0], |e| e.to)
+    }
+
+    fn cone<'a>(
+        &'a self,
+        start: NodeId,
+        max_depth: Option<usize>,
+        adjacent: impl Fn(NodeId) -> &'a [usize],
+        endpoint: impl Fn(&Edge) -> NodeId,
+    ) -> Vec<NodeId> {
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0usize)]);
+        let mut cone = Vec::new();
+
+        while let Some((node, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            for &e in adjacent(node) {
+                let neighbour = endpoint(&self.edges[e]);
+                if seen.insert(neighbour) {
+                    cone.push(neighbour);
+                    queue.push_back((neighbour, depth + 1));
+                }
+            }
+        }
+
+        cone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // clk -> p_reg -> q -> p_out -> y, with a feedback load q -> p_reg
+    fn sample_graph() -> (Topology, [NodeId; 5]) {
+        let mut graph = Topology::default();
+        let clk = graph.add_node(":tb:clk", NodeKind::Port);
+        let p_reg = graph.add_node(":tb:p_reg", NodeKind::Process);
+        let q = graph.add_node(":tb:q", NodeKind::Signal);
+        let p_out = graph.add_node(":tb:p_out", NodeKind::Process);
+        let y = graph.add_node(":tb:y", NodeKind::Signal);
+
+        graph.add_edge(clk, p_reg, EdgeKind::Load);
+        graph.add_edge(p_reg, q, EdgeKind::Drive);
+        graph.add_edge(q, p_reg, EdgeKind::Load);
+        graph.add_edge(q, p_out, EdgeKind::Load);
+        graph.add_edge(p_out, y, EdgeKind::Drive);
+
+        (graph, [clk, p_reg, q, p_out, y])
+    }
+
+    #[test]
+    fn drivers_and_loads_follow_edge_kinds() {
+        let (graph, [clk, p_reg, q, p_out, _]) = sample_graph();
+
+        assert_eq!(graph.sources(q, EdgeKind::Drive), vec![p_reg]);
+        assert_eq!(graph.targets(p_reg, EdgeKind::Drive), vec![q]);
+        assert_eq!(graph.targets(q, EdgeKind::Load), vec![p_reg, p_out]);
+        assert_eq!(graph.sensitive_processes(clk), vec![p_reg]);
+    }
+
+    #[test]
+    fn duplicate_edges_are_ignored() {
+        let (mut graph, [_, p_reg, q, _, _]) = sample_graph();
+
+        graph.add_edge(p_reg, q, EdgeKind::Drive);
+
+        assert_eq!(graph.edges.len(), 5);
+        assert_eq!(graph.sources(q, EdgeKind::Drive), vec![p_reg]);
+    }
+
+    #[test]
+    fn find_is_case_insensitive() {
+        let (graph, [clk, ..]) = sample_graph();
+
+        assert_eq!(graph.find(":TB:CLK"), Some(clk));
+        assert_eq!(graph.find(":tb:missing"), None);
+    }
+
+    #[test]
+    fn same_name_nodes_stay_distinct() {
+        let (mut graph, [clk, ..]) = sample_graph();
+
+        let other = graph.add_node(":tb:clk", NodeKind::Signal);
+
+        assert_ne!(other, clk);
+        assert_eq!(graph.named(":tb:clk"), [clk, other]);
+        assert_eq!(graph.find(":tb:clk"), Some(clk));
+        assert!(graph.named("").is_empty());
+    }
+
+    #[test]
+    fn fan_out_cone_terminates_on_feedback() {
+        let (graph, [clk, p_reg, q, p_out, y]) = sample_graph();
+
+        assert_eq!(graph.fan_out(clk, None), vec![p_reg, q, p_out, y]);
+        assert_eq!(graph.fan_out(clk, Some(2)), vec![p_reg, q]);
+    }
+
+    #[test]
+    fn fan_in_cone_walks_edges_backwards() {
+        let (graph, [clk, p_reg, q, p_out, y]) = sample_graph();
+
+        assert_eq!(graph.fan_in(y, None), vec![p_out, q, p_reg, clk]);
+        assert_eq!(graph.fan_in(y, Some(1)), vec![p_out]);
+        assert!(graph.fan_in(clk, None).is_empty());
+    }
+}
@@ -0,0 +1,507 @@
+//! `ieee.fixed_pkg` values via [`UFixed`] and [`SFixed`].
+//!
+//! A `ufixed` or `sfixed` signal is a `std_logic` array whose index range
+//! gives the position of the binary point: index `i` has weight `2**i`, so
+//! `sfixed(3 downto -4)` has four integer and four fraction bits.  Read one
+//! with [`Handle::get_sfixed`] or [`Handle::get_ufixed`], which take the
+//! range from the object's type, and write a number with
+//! [`Handle::put_sfixed`] or [`Handle::put_ufixed`], which quantize it the
+//! way `to_sfixed` and `to_ufixed` do.
+//!
+//! Conversions to and from numbers are limited to 64 bits.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{FixedStyle, Handle, PutValueMode};
+//!
+//! fn scale(sample: &Handle, gain: f64) -> Result<(), vhpi::Error> {
+//!     let value = sample.get_sfixed()?.to_f64().unwrap_or(0.0);
+//!     sample.put_sfixed(value * gain, FixedStyle::default(), PutValueMode::Deposit)
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::{Error, Format, Handle, LogicVal, LogicVec, OneToOne, PutValueMode, Value};
+
+/// Widest vector that can be converted to or from a number.
+const MAX_WIDTH: usize = 64;
+
+/// How bits below the least significant index are handled, as
+/// `fixed_round_style_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FixedRound {
+    /// Round to the nearest value, ties to even (`fixed_round`).
+    #[default]
+    Round,
+    /// Discard the extra bits, rounding towards negative infinity
+    /// (`fixed_truncate`).
+    Truncate,
+}
+
+/// How values outside the representable range are handled, as
+/// `fixed_overflow_style_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FixedOverflow {
+    /// Clamp to the largest or smallest value (`fixed_saturate`).
+    #[default]
+    Saturate,
+    /// Keep the low order bits (`fixed_wrap`).
+    Wrap,
+}
+
+/// Quantization settings for conversions into a fixed-point format.
+///
+/// The default matches the `fixed_pkg` defaults of rounding and
+/// saturation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FixedStyle {
+    /// Rounding of bits below the least significant index.
+    pub round: FixedRound,
+    /// Handling of values outside the range.
+    pub overflow: FixedOverflow,
+}
+
+/// Divide `num` by `den`, which must be positive, rounding as `round`.
+fn round_div(num: i128, den: i128, round: FixedRound) -> i128 {
+    let quot = num.div_euclid(den);
+    let rem = num.rem_euclid(den);
+    match round {
+        FixedRound::Truncate => quot,
+        FixedRound::Round if 2 * rem > den || (2 * rem == den && quot & 1 == 1) => quot + 1,
+        FixedRound::Round => quot,
+    }
+}
+
+/// Fit `value` into `width` bits, two's complement when `signed`.
+fn fit(value: i128, width: usize, signed: bool, overflow: FixedOverflow) -> i128 {
+    let (min, max) = if signed {
+        (-(1i128 << (width - 1)), (1i128 << (width - 1)) - 1)
+    } else {
+        (0, (1i128 << width) - 1)
+    };
+    match overflow {
+        FixedOverflow::Saturate => value.clamp(min, max),
+        FixedOverflow::Wrap => {
+            let wrapped = value.rem_euclid(1i128 << width);
+            if wrapped > max {
+                wrapped - (1i128 << width)
+            } else {
+                wrapped
+            }
+        }
+    }
+}
+
+/// Split a finite `f64` into an integer mantissa and a binary exponent.
+fn decompose(value: f64) -> (i128, i32) {
+    let bits = value.to_bits();
+    let exponent = i32::try_from((bits >> 52) & 0x7ff).unwrap_or(0);
+    let fraction = i128::from(bits & ((1 << 52) - 1));
+    let (mantissa, exponent) = if exponent == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), exponent - 1075)
+    };
+    if value.is_sign_negative() {
+        (-mantissa, exponent)
+    } else {
+        (mantissa, exponent)
+    }
+}
+
+fn check_range(high: i32, low: i32) -> Result<usize, Error> {
+    if high < low {
+        return Err(format!("fixed-point range {high} downto {low} is null")
+            .as_str()
+            .into());
+    }
+    usize::try_from(i64::from(high) - i64::from(low) + 1)
+        .map_err(|_| Error::from("fixed-point range is too wide"))
+}
+
+fn check_width(width: usize) -> Result<(), Error> {
+    if width > MAX_WIDTH {
+        Err(
+            format!("fixed-point values wider than {MAX_WIDTH} bits cannot be converted")
+                .as_str()
+                .into(),
+        )
+    } else {
+        Ok(())
+    }
+}
+
+macro_rules! fixed_type {
+    ($name:ident, $signed:literal, $vhdl:literal) => {
+        #[doc = concat!("A `", $vhdl, "` value from `ieee.fixed_pkg`.")]
+        ///
+        /// Bits are stored most significant first, from index
+        /// [`high`](Self::high) down to index [`low`](Self::low).
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name {
+            high: i32,
+            low: i32,
+            bits: LogicVec,
+        }
+
+        impl $name {
+            /// Wraps the bits of a `high downto low` value.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the range is null or does not match the
+            /// number of bits.
+            pub fn new(bits: LogicVec, high: i32, low: i32) -> Result<Self, Error> {
+                if check_range(high, low)? != bits.len() {
+                    return Err(format!(
+                        "{} bits do not fit {} ({high} downto {low})",
+                        bits.len(),
+                        $vhdl
+                    )
+                    .as_str()
+                    .into());
+                }
+                Ok(Self { high, low, bits })
+            }
+
+            #[doc = concat!("Converts `value` to a `", $vhdl, "(high downto low)`, as `to_", $vhdl, "`.")]
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if `value` is not finite, or the range is
+            /// null or wider than 64 bits.
+            pub fn from_f64(
+                value: f64,
+                high: i32,
+                low: i32,
+                style: FixedStyle,
+            ) -> Result<Self, Error> {
+                if !value.is_finite() {
+                    return Err(format!("cannot convert {value} to {}", $vhdl).as_str().into());
+                }
+                let (mantissa, exponent) = decompose(value);
+                // Clamping the shift keeps the arithmetic in range without
+                // changing the result for any width up to 64 bits.
+                let shift = i64::from(exponent) - i64::from(low);
+                let scaled = if shift >= 0 {
+                    (mantissa << shift.min(70), 1)
+                } else {
+                    (mantissa, 1i128 << (-shift).min(100))
+                };
+                Self::from_scaled(scaled, high, low, style)
+            }
+
+            #[doc = concat!("Converts `num / den` to a `", $vhdl, "(high downto low)`.")]
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if `den` is zero, the range is null or wider
+            /// than 64 bits, or the intermediate result overflows `i128`.
+            pub fn from_rational(
+                num: i128,
+                den: i128,
+                high: i32,
+                low: i32,
+                style: FixedStyle,
+            ) -> Result<Self, Error> {
+                if den == 0 {
+                    return Err("fixed-point denominator is zero".into());
+                }
+                let (num, den) = if den < 0 { (-num, -den) } else { (num, den) };
+                let scale = 2i128.checked_pow(low.unsigned_abs());
+                let scaled = match scale {
+                    Some(scale) if low < 0 => num.checked_mul(scale).map(|num| (num, den)),
+                    Some(scale) => den.checked_mul(scale).map(|den| (num, den)),
+                    None => None,
+                }
+                .ok_or_else(|| Error::from("fixed-point conversion overflows"))?;
+                Self::from_scaled(scaled, high, low, style)
+            }
+
+            fn from_scaled(
+                (num, den): (i128, i128),
+                high: i32,
+                low: i32,
+                style: FixedStyle,
+            ) -> Result<Self, Error> {
+                let width = check_range(high, low)?;
+                check_width(width)?;
+                let value = fit(round_div(num, den, style.round), width, $signed, style.overflow);
+                let bits: LogicVec = (0..width)
+                    .rev()
+                    .map(|i| if (value >> i) & 1 == 1 { LogicVal::One } else { LogicVal::Zero })
+                    .collect();
+                Ok(Self { high, low, bits })
+            }
+
+            /// Index of the most significant bit.
+            #[must_use]
+            pub fn high(&self) -> i32 {
+                self.high
+            }
+
+            /// Index of the least significant bit; negative for fraction
+            /// bits.
+            #[must_use]
+            pub fn low(&self) -> i32 {
+                self.low
+            }
+
+            /// The bits, most significant first.
+            #[must_use]
+            pub fn bits(&self) -> &LogicVec {
+                &self.bits
+            }
+
+            /// Wraps the bits as a [`Value::LogicVec`].
+            #[must_use]
+            pub fn as_value(&self) -> Value {
+                self.bits.as_value()
+            }
+
+            /// The value as `mantissa * 2**low`, or `None` if a bit is a
+            /// metavalue or the vector is wider than 64 bits.
+            #[must_use]
+            pub fn mantissa(&self) -> Option<i128> {
+                if self.bits.len() > MAX_WIDTH {
+                    return None;
+                }
+                let mut value: i128 = 0;
+                for val in self.bits.iter() {
+                    value = match val.to_x01() {
+                        LogicVal::Zero => value << 1,
+                        LogicVal::One => (value << 1) | 1,
+                        _ => return None,
+                    };
+                }
+                if $signed && self.bits.get(0).map(LogicVal::to_x01) == Some(LogicVal::One) {
+                    value -= 1i128 << self.bits.len();
+                }
+                Some(value)
+            }
+
+            /// The exact value as a numerator and a power of two
+            /// denominator, or `None` under the same conditions as
+            /// [`mantissa`](Self::mantissa) or if the value does not fit in
+            /// an `i128`.
+            #[must_use]
+            pub fn to_rational(&self) -> Option<(i128, i128)> {
+                let mantissa = self.mantissa()?;
+                let scale = 2i128.checked_pow(self.low.unsigned_abs())?;
+                if self.low < 0 {
+                    Some((mantissa, scale))
+                } else {
+                    Some((mantissa.checked_mul(scale)?, 1))
+                }
+            }
+
+            /// The value as an `f64`, as `to_real`, or `None` if a bit is a
+            /// metavalue or the vector is wider than 64 bits.
+            #[must_use]
+            pub fn to_f64(&self) -> Option<f64> {
+                Some(self.mantissa()? as f64 * 2f64.powi(self.low))
+            }
+
+            /// Converts to a `high downto low` value, as `resize`.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if this value contains a metavalue or under
+            /// the same conditions as [`from_rational`](Self::from_rational).
+            pub fn resize(&self, high: i32, low: i32, style: FixedStyle) -> Result<Self, Error> {
+                let (num, den) = self
+                    .to_rational()
+                    .ok_or_else(|| Error::from("cannot resize a value with metavalues"))?;
+                Self::from_rational(num, den, high, low, style)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.to_f64() {
+                    Some(value) => write!(f, "{value}"),
+                    None => write!(f, "{}", self.bits),
+                }
+            }
+        }
+    };
+}
+
+fixed_type!(UFixed, false, "ufixed");
+fixed_type!(SFixed, true, "sfixed");
+
+impl Handle {
+    /// Index range of a fixed-point object as `(high, low)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object's type is not a descending array.
+    pub fn fixed_range(&self) -> Result<(i32, i32), Error> {
+        let mut range = self.handle(OneToOne::Type).index_range();
+        let high = range
+            .next()
+            .ok_or_else(|| Error::from("object has no index range"))?;
+        let low = range.last().unwrap_or(high);
+        if high < low {
+            return Err(format!("index range {high} to {low} is not descending")
+                .as_str()
+                .into());
+        }
+        Ok((high, low))
+    }
+
+    /// Read an `ieee.fixed_pkg` `ufixed` object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object is not a logic vector with a
+    /// descending range, or the simulator rejects the read.
+    pub fn get_ufixed(&self) -> Result<UFixed, Error> {
+        let (high, low) = self.fixed_range()?;
+        UFixed::new(self.get_logic_vec()?, high, low)
+    }
+
+    /// Read an `ieee.fixed_pkg` `sfixed` object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object is not a logic vector with a
+    /// descending range, or the simulator rejects the read.
+    pub fn get_sfixed(&self) -> Result<SFixed, Error> {
+        let (high, low) = self.fixed_range()?;
+        SFixed::new(self.get_logic_vec()?, high, low)
+    }
+
+    /// Write `value` to a `ufixed` object, quantized to its range as
+    /// `to_ufixed` with `style` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be converted or the simulator
+    /// rejects the write.
+    pub fn put_ufixed(
+        &self,
+        value: f64,
+        style: FixedStyle,
+        mode: PutValueMode,
+    ) -> Result<(), Error> {
+        let (high, low) = self.fixed_range()?;
+        self.put_value(UFixed::from_f64(value, high, low, style)?.as_value(), mode)
+    }
+
+    /// Write `value` to an `sfixed` object, quantized to its range as
+    /// `to_sfixed` with `style` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be converted or the simulator
+    /// rejects the write.
+    pub fn put_sfixed(
+        &self,
+        value: f64,
+        style: FixedStyle,
+        mode: PutValueMode,
+    ) -> Result<(), Error> {
+        let (high, low) = self.fixed_range()?;
+        self.put_value(SFixed::from_f64(value, high, low, style)?.as_value(), mode)
+    }
+
+    pub(crate) fn get_logic_vec(&self) -> Result<LogicVec, Error> {
+        match self.get_value(Format::LogicVec)? {
+            Value::LogicVec(bits) => Ok(bits),
+            _ => Err("object is not a logic vector".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRUNC_WRAP: FixedStyle = FixedStyle {
+        round: FixedRound::Truncate,
+        overflow: FixedOverflow::Wrap,
+    };
+
+    fn sfixed(bits: &str, high: i32, low: i32) -> SFixed {
+        SFixed::new(LogicVec::from(bits), high, low).unwrap()
+    }
+
+    #[test]
+    fn decodes_fraction_bits_from_range() {
+        assert_eq!(sfixed("00110100", 3, -4).to_f64(), Some(3.25));
+        assert_eq!(sfixed("11110000", 3, -4).to_f64(), Some(-1.0));
+        assert_eq!(sfixed("1000", 3, 0).to_f64(), Some(-8.0));
+        assert_eq!(
+            UFixed::new(LogicVec::from("11110000"), 3, -4)
+                .unwrap()
+                .to_f64(),
+            Some(15.0)
+        );
+        assert_eq!(sfixed("0101", 5, 2).to_rational(), Some((20, 1)));
+        assert_eq!(sfixed("0101", -1, -4).to_rational(), Some((5, 16)));
+        assert_eq!(sfixed("01X1", 1, -2).to_f64(), None);
+        assert!(SFixed::new(LogicVec::from("010"), 3, -4).is_err());
+    }
+
+    #[test]
+    fn rounds_to_nearest_even() {
+        let style = FixedStyle::default();
+        let round = |x: f64| SFixed::from_f64(x, 3, -1, style).unwrap().to_f64();
+        assert_eq!(round(1.25), Some(1.0));
+        assert_eq!(round(1.75), Some(2.0));
+        assert_eq!(round(1.3), Some(1.5));
+        assert_eq!(round(-1.25), Some(-1.0));
+        assert_eq!(round(-1.75), Some(-2.0));
+    }
+
+    #[test]
+    fn truncates_towards_negative_infinity() {
+        let trunc = |x: f64| SFixed::from_f64(x, 3, -1, TRUNC_WRAP).unwrap().to_f64();
+        assert_eq!(trunc(1.9), Some(1.5));
+        assert_eq!(trunc(-1.1), Some(-1.5));
+    }
+
+    #[test]
+    fn saturates_or_wraps_on_overflow() {
+        let style = FixedStyle::default();
+        assert_eq!(
+            SFixed::from_f64(100.0, 3, -2, style).unwrap().to_f64(),
+            Some(7.75)
+        );
+        assert_eq!(
+            SFixed::from_f64(-100.0, 3, -2, style).unwrap().to_f64(),
+            Some(-8.0)
+        );
+        assert_eq!(
+            UFixed::from_f64(-1.0, 3, -2, style).unwrap().to_f64(),
+            Some(0.0)
+        );
+        assert_eq!(
+            SFixed::from_f64(9.0, 3, 0, TRUNC_WRAP).unwrap().to_f64(),
+            Some(-7.0)
+        );
+        assert_eq!(
+            UFixed::from_f64(1e300, 7, 0, TRUNC_WRAP).unwrap().to_f64(),
+            Some(0.0)
+        );
+        assert_eq!(
+            UFixed::from_f64(1e300, 7, 0, style).unwrap().to_f64(),
+            Some(255.0)
+        );
+    }
+
+    #[test]
+    fn converts_rationals_and_resizes() {
+        let style = FixedStyle::default();
+        let third = SFixed::from_rational(1, 3, 1, -6, style).unwrap();
+        assert_eq!(third.to_rational(), Some((21, 64)));
+        assert_eq!(SFixed::from_rational(-1, -3, 1, -6, style).unwrap(), third);
+        assert_eq!(third.resize(0, -2, style).unwrap().to_f64(), Some(0.25));
+        assert!(SFixed::from_rational(1, 0, 1, -6, style).is_err());
+        assert!(SFixed::from_f64(f64::NAN, 1, -6, style).is_err());
+        assert!(SFixed::from_f64(1.0, 40, -40, style).is_err());
+    }
+}
@@ -0,0 +1,667 @@
+//! `ieee.float_pkg` values via [`Float`].
+//!
+//! A `float` signal is a `std_logic` array `e downto -f`: index `e` holds
+//! the sign, indices `e-1 downto 0` the biased exponent and `-1 downto -f`
+//! the fraction, laid out like IEEE 754.  `float32` and `float64` are
+//! `float(8 downto -23)` and `float(11 downto -52)`, and convert to `f32`
+//! and `f64` bit for bit.  Other widths are rounded as `float_pkg` does,
+//! under the control of a [`FloatStyle`].
+//!
+//! Read one with [`Handle::get_float`], which takes the exponent and
+//! fraction widths from the object's type, and write a number with
+//! [`Handle::put_float`].  Special values are handled as follows:
+//!
+//! - A bit that is not `0`, `1`, `L` or `H` makes the whole value a
+//!   metavalue: [`Float::classify`] returns [`FloatClass::Metavalue`] and
+//!   the numeric conversions return `None`.
+//! - Denormal values are read and written as such unless
+//!   [`FloatStyle::denormalize`] is cleared, in which case they are
+//!   flushed to zero of the same sign.
+//! - NaNs keep their sign and whether they are quiet.  The payload is
+//!   truncated to the fraction width, and a signalling NaN whose payload
+//!   would become zero gets its lowest fraction bit set.
+//!
+//! Formats are limited to 64 bits.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{FloatClass, Handle, Severity};
+//!
+//! fn check_result(result: &Handle, expected: f32) -> Result<(), vhpi::Error> {
+//!     let actual = result.get_float()?;
+//!     if actual.classify() == FloatClass::Metavalue || actual.to_f32() != Some(expected) {
+//!         vhpi::assert(Severity::Error, format!("expected {expected}, got {actual}"));
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::{Error, Handle, LogicVal, LogicVec, PutValueMode, Value};
+
+/// Widest format that can be converted to or from a number.
+const MAX_WIDTH: usize = 64;
+
+/// Exponent and fraction widths of a `float` subtype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FloatFormat {
+    exponent_width: usize,
+    fraction_width: usize,
+}
+
+/// A number unpacked from its encoding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unpacked {
+    /// Fraction bits aligned to the top of the payload, so the most
+    /// significant bit is set for a quiet NaN.
+    Nan {
+        negative: bool,
+        payload: u64,
+    },
+    Inf {
+        negative: bool,
+    },
+    /// `mantissa * 2**exponent`.
+    Finite {
+        negative: bool,
+        mantissa: u64,
+        exponent: i64,
+    },
+}
+
+impl FloatFormat {
+    /// `float32`, the format of `f32`.
+    pub const FLOAT32: Self = Self {
+        exponent_width: 8,
+        fraction_width: 23,
+    };
+
+    /// `float64`, the format of `f64`.
+    pub const FLOAT64: Self = Self {
+        exponent_width: 11,
+        fraction_width: 52,
+    };
+
+    /// A format with the given exponent and fraction widths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are fewer than two exponent bits, no
+    /// fraction bits, or more than 64 bits in total.
+    pub fn new(exponent_width: usize, fraction_width: usize) -> Result<Self, Error> {
+        if exponent_width < 2 || fraction_width < 1 {
+            return Err(format!(
+                "float format needs at least 2 exponent bits and 1 fraction bit, \
+                 not {exponent_width} and {fraction_width}"
+            )
+            .as_str()
+            .into());
+        }
+        if 1 + exponent_width + fraction_width > MAX_WIDTH {
+            return Err(
+                format!("float formats wider than {MAX_WIDTH} bits cannot be converted")
+                    .as_str()
+                    .into(),
+            );
+        }
+        Ok(Self {
+            exponent_width,
+            fraction_width,
+        })
+    }
+
+    /// The format of a `float(high downto low)` subtype.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `low` is not negative or under the same
+    /// conditions as [`new`](Self::new).
+    pub fn from_range(high: i32, low: i32) -> Result<Self, Error> {
+        match (usize::try_from(high), usize::try_from(-i64::from(low))) {
+            (Ok(exponent_width), Ok(fraction_width)) => Self::new(exponent_width, fraction_width),
+            _ => Err(
+                format!("float range {high} downto {low} has no fraction bits")
+                    .as_str()
+                    .into(),
+            ),
+        }
+    }
+
+    /// Number of exponent bits.
+    #[must_use]
+    pub fn exponent_width(&self) -> usize {
+        self.exponent_width
+    }
+
+    /// Number of fraction bits, not counting the hidden bit.
+    #[must_use]
+    pub fn fraction_width(&self) -> usize {
+        self.fraction_width
+    }
+
+    /// Total number of bits, including the sign.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        1 + self.exponent_width + self.fraction_width
+    }
+
+    fn bias(self) -> i64 {
+        (1 << (self.exponent_width - 1)) - 1
+    }
+
+    fn max_exponent(self) -> u64 {
+        (1 << self.exponent_width) - 1
+    }
+
+    fn fraction_mask(self) -> u64 {
+        (1 << self.fraction_width) - 1
+    }
+
+    fn sign_bit(self, negative: bool) -> u64 {
+        u64::from(negative) << (self.exponent_width + self.fraction_width)
+    }
+
+    fn unpack(self, raw: u64, denormalize: bool) -> Unpacked {
+        let negative = raw & self.sign_bit(true) != 0;
+        let exponent = (raw >> self.fraction_width) & self.max_exponent();
+        let fraction = raw & self.fraction_mask();
+        let min_exponent = 1 - self.bias() - self.fraction_width as i64;
+        if exponent == self.max_exponent() {
+            if fraction == 0 {
+                Unpacked::Inf { negative }
+            } else {
+                let payload = fraction << (64 - self.fraction_width);
+                Unpacked::Nan { negative, payload }
+            }
+        } else if exponent == 0 {
+            Unpacked::Finite {
+                negative,
+                mantissa: if denormalize { fraction } else { 0 },
+                exponent: min_exponent,
+            }
+        } else {
+            Unpacked::Finite {
+                negative,
+                mantissa: fraction | (1 << self.fraction_width),
+                exponent: min_exponent + exponent as i64 - 1,
+            }
+        }
+    }
+
+    fn pack(self, value: Unpacked, style: FloatStyle) -> u64 {
+        let f = self.fraction_width;
+        match value {
+            Unpacked::Inf { negative } => self.sign_bit(negative) | self.max_exponent() << f,
+            Unpacked::Nan { negative, payload } => {
+                let fraction = (payload >> (64 - f)).max(1);
+                self.sign_bit(negative) | self.max_exponent() << f | fraction
+            }
+            Unpacked::Finite {
+                negative,
+                mantissa: 0,
+                ..
+            } => self.sign_bit(negative),
+            Unpacked::Finite {
+                negative,
+                mantissa,
+                exponent,
+            } => {
+                let min_exponent = 1 - self.bias();
+                let top = exponent + i64::from(63 - mantissa.leading_zeros());
+                let lsb = top.max(min_exponent) - f as i64;
+                let shift = lsb - exponent;
+                let rounded = if shift <= 0 {
+                    mantissa << -shift
+                } else {
+                    round_shift(mantissa, shift, negative, style.round)
+                };
+                // Rounding up may carry into a new most significant bit,
+                // which shifts out a zero.
+                let (rounded, lsb) = if rounded >> (f + 1) == 0 {
+                    (rounded, lsb)
+                } else {
+                    (rounded >> 1, lsb + 1)
+                };
+                let sign = self.sign_bit(negative);
+                if rounded >> f == 0 {
+                    return if style.denormalize {
+                        sign | rounded
+                    } else {
+                        sign
+                    };
+                }
+                let biased = lsb + f as i64 + self.bias();
+                if biased >= self.max_exponent() as i64 {
+                    return if style.round.overflows_to_inf(negative) {
+                        sign | self.max_exponent() << f
+                    } else {
+                        sign | (self.max_exponent() - 1) << f | self.fraction_mask()
+                    };
+                }
+                sign | (biased as u64) << f | (rounded & self.fraction_mask())
+            }
+        }
+    }
+}
+
+/// How results that are not exactly representable are rounded, as
+/// `round_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatRound {
+    /// Round to the nearest value, ties to even (`round_nearest`).
+    #[default]
+    Nearest,
+    /// Round towards positive infinity (`round_inf`).
+    Inf,
+    /// Round towards negative infinity (`round_neginf`).
+    NegInf,
+    /// Round towards zero (`round_zero`).
+    Zero,
+}
+
+impl FloatRound {
+    fn rounds_up(self, negative: bool, odd: bool, rem: u128, half: u128) -> bool {
+        match self {
+            Self::Nearest => rem > half || (rem == half && odd),
+            Self::Inf => !negative && rem != 0,
+            Self::NegInf => negative && rem != 0,
+            Self::Zero => false,
+        }
+    }
+
+    fn overflows_to_inf(self, negative: bool) -> bool {
+        match self {
+            Self::Nearest => true,
+            Self::Inf => !negative,
+            Self::NegInf => negative,
+            Self::Zero => false,
+        }
+    }
+}
+
+/// Shift the magnitude `mantissa` right by `shift`, which must be
+/// positive, rounding as `round`.
+fn round_shift(mantissa: u64, shift: i64, negative: bool, round: FloatRound) -> u64 {
+    // Any shift beyond 64 discards the whole mantissa, and clamping keeps
+    // the remainder below half as it should be.
+    let shift = shift.min(100) as u32;
+    let mantissa = u128::from(mantissa);
+    let quot = mantissa >> shift;
+    let rem = mantissa & ((1 << shift) - 1);
+    let up = round.rounds_up(negative, quot & 1 == 1, rem, 1 << (shift - 1));
+    (quot + u128::from(up)) as u64
+}
+
+/// Rounding and denormal handling for conversions between formats, as the
+/// `float_pkg` generics.
+///
+/// The default rounds to nearest and supports denormals, like the
+/// `float_pkg` defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FloatStyle {
+    /// Rounding of results that are not exactly representable.
+    pub round: FloatRound,
+    /// Whether denormal values are supported.  When cleared, denormal
+    /// inputs are read as zero and results below the smallest normal
+    /// number become zero, as `float_denormalize => false`.
+    pub denormalize: bool,
+}
+
+impl Default for FloatStyle {
+    fn default() -> Self {
+        Self {
+            round: FloatRound::Nearest,
+            denormalize: true,
+        }
+    }
+}
+
+/// Classification of a `float` value, as `valid_fpstate` returned by
+/// `classfp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatClass {
+    /// A signalling NaN (`nan`).
+    SignalingNan,
+    /// A quiet NaN (`quiet_nan`).
+    QuietNan,
+    /// Negative infinity (`neg_inf`).
+    NegInf,
+    /// A negative normal number (`neg_normal`).
+    NegNormal,
+    /// A negative denormal number (`neg_denormal`).
+    NegDenormal,
+    /// Negative zero (`neg_zero`).
+    NegZero,
+    /// Positive zero (`pos_zero`).
+    PosZero,
+    /// A positive denormal number (`pos_denormal`).
+    PosDenormal,
+    /// A positive normal number (`pos_normal`).
+    PosNormal,
+    /// Positive infinity (`pos_inf`).
+    PosInf,
+    /// A bit is a metavalue (`isx`).
+    Metavalue,
+}
+
+/// A `float` value from `ieee.float_pkg`.
+///
+/// Bits are stored most significant first: the sign, the exponent, then
+/// the fraction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Float {
+    format: FloatFormat,
+    bits: LogicVec,
+}
+
+impl Float {
+    /// Wraps the bits of a value in `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of bits does not match the format.
+    pub fn new(bits: LogicVec, format: FloatFormat) -> Result<Self, Error> {
+        if bits.len() != format.width() {
+            return Err(format!(
+                "{} bits do not fit float({} downto -{})",
+                bits.len(),
+                format.exponent_width,
+                format.fraction_width
+            )
+            .as_str()
+            .into());
+        }
+        Ok(Self { format, bits })
+    }
+
+    /// Converts `value` to a `float32` with the same bits.
+    #[must_use]
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_raw(FloatFormat::FLOAT32, u64::from(value.to_bits()))
+    }
+
+    /// Converts `value` to a `float64` with the same bits.
+    #[must_use]
+    pub fn from_f64(value: f64) -> Self {
+        Self::from_raw(FloatFormat::FLOAT64, value.to_bits())
+    }
+
+    /// Converts `value` to `format`, rounding as `style`.
+    #[must_use]
+    pub fn from_f64_with(value: f64, format: FloatFormat, style: FloatStyle) -> Self {
+        let unpacked = FloatFormat::FLOAT64.unpack(value.to_bits(), true);
+        Self::from_raw(format, format.pack(unpacked, style))
+    }
+
+    fn from_raw(format: FloatFormat, raw: u64) -> Self {
+        let bits = (0..format.width())
+            .rev()
+            .map(|i| {
+                if (raw >> i) & 1 == 1 {
+                    LogicVal::One
+                } else {
+                    LogicVal::Zero
+                }
+            })
+            .collect();
+        Self { format, bits }
+    }
+
+    /// Exponent and fraction widths.
+    #[must_use]
+    pub fn format(&self) -> FloatFormat {
+        self.format
+    }
+
+    /// The bits, most significant first.
+    #[must_use]
+    pub fn bits(&self) -> &LogicVec {
+        &self.bits
+    }
+
+    /// Wraps the bits as a [`Value::LogicVec`].
+    #[must_use]
+    pub fn as_value(&self) -> Value {
+        self.bits.as_value()
+    }
+
+    /// The encoding as an integer, or `None` if a bit is a metavalue.
+    #[must_use]
+    pub fn to_bits(&self) -> Option<u64> {
+        self.bits
+            .iter()
+            .try_fold(0u64, |raw, val| match val.to_x01() {
+                LogicVal::Zero => Some(raw << 1),
+                LogicVal::One => Some((raw << 1) | 1),
+                _ => None,
+            })
+    }
+
+    /// Classify the value, as `classfp`.
+    #[must_use]
+    pub fn classify(&self) -> FloatClass {
+        let Some(raw) = self.to_bits() else {
+            return FloatClass::Metavalue;
+        };
+        let format = self.format;
+        let negative = raw & format.sign_bit(true) != 0;
+        let exponent = (raw >> format.fraction_width) & format.max_exponent();
+        let fraction = raw & format.fraction_mask();
+        let quiet = fraction >> (format.fraction_width - 1) == 1;
+        match (exponent, fraction, negative) {
+            (e, 0, false) if e == format.max_exponent() => FloatClass::PosInf,
+            (e, 0, true) if e == format.max_exponent() => FloatClass::NegInf,
+            (e, _, _) if e == format.max_exponent() && quiet => FloatClass::QuietNan,
+            (e, _, _) if e == format.max_exponent() => FloatClass::SignalingNan,
+            (0, 0, false) => FloatClass::PosZero,
+            (0, 0, true) => FloatClass::NegZero,
+            (0, _, false) => FloatClass::PosDenormal,
+            (0, _, true) => FloatClass::NegDenormal,
+            (_, _, false) => FloatClass::PosNormal,
+            (_, _, true) => FloatClass::NegNormal,
+        }
+    }
+
+    fn unpack(&self, style: FloatStyle) -> Option<Unpacked> {
+        Some(self.format.unpack(self.to_bits()?, style.denormalize))
+    }
+
+    /// The value as an `f64`, or `None` if a bit is a metavalue.
+    ///
+    /// Formats wider than `float64` are rounded to nearest.
+    #[must_use]
+    pub fn to_f64(&self) -> Option<f64> {
+        self.to_f64_with(FloatStyle::default())
+    }
+
+    /// The value as an `f64`, rounding as `style`, or `None` if a bit is a
+    /// metavalue.
+    #[must_use]
+    pub fn to_f64_with(&self, style: FloatStyle) -> Option<f64> {
+        let raw = FloatFormat::FLOAT64.pack(self.unpack(style)?, style);
+        Some(f64::from_bits(raw))
+    }
+
+    /// The value as an `f32`, or `None` if a bit is a metavalue.
+    ///
+    /// Formats wider than `float32` are rounded to nearest.
+    #[must_use]
+    pub fn to_f32(&self) -> Option<f32> {
+        self.to_f32_with(FloatStyle::default())
+    }
+
+    /// The value as an `f32`, rounding as `style`, or `None` if a bit is a
+    /// metavalue.
+    #[must_use]
+    pub fn to_f32_with(&self, style: FloatStyle) -> Option<f32> {
+        let raw = FloatFormat::FLOAT32.pack(self.unpack(style)?, style);
+        Some(f32::from_bits(raw as u32))
+    }
+
+    /// Converts to `format`, as `resize`, or `None` if a bit is a
+    /// metavalue.
+    #[must_use]
+    pub fn resize(&self, format: FloatFormat, style: FloatStyle) -> Option<Self> {
+        let raw = format.pack(self.unpack(style)?, style);
+        Some(Self::from_raw(format, raw))
+    }
+}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_f64() {
+            Some(value) => write!(f, "{value}"),
+            None => write!(f, "{}", self.bits),
+        }
+    }
+}
+
+impl Handle {
+    /// Exponent and fraction widths of a `float` object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object's type is not a descending array
+    /// with a valid `float` range.
+    pub fn float_format(&self) -> Result<FloatFormat, Error> {
+        let (high, low) = self.fixed_range()?;
+        FloatFormat::from_range(high, low)
+    }
+
+    /// Read an `ieee.float_pkg` `float` object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object is not a logic vector with a valid
+    /// `float` range, or the simulator rejects the read.
+    pub fn get_float(&self) -> Result<Float, Error> {
+        let format = self.float_format()?;
+        Float::new(self.get_logic_vec()?, format)
+    }
+
+    /// Write `value` to a `float` object, rounded to its format as
+    /// `style`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object does not have a valid `float` range
+    /// or the simulator rejects the write.
+    pub fn put_float(
+        &self,
+        value: f64,
+        style: FloatStyle,
+        mode: PutValueMode,
+    ) -> Result<(), Error> {
+        let format = self.float_format()?;
+        self.put_value(Float::from_f64_with(value, format, style).as_value(), mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALF: FloatFormat = FloatFormat {
+        exponent_width: 5,
+        fraction_width: 10,
+    };
+
+    fn half(value: f64) -> u64 {
+        Float::from_f64_with(value, HALF, FloatStyle::default())
+            .to_bits()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trips_native_formats() {
+        for value in [0.0, -0.0, 1.5, -3.75e-30, f32::MIN_POSITIVE / 8.0, f32::MAX] {
+            let float = Float::from_f32(value);
+            assert_eq!(float.format(), FloatFormat::FLOAT32);
+            assert_eq!(float.to_f32().map(f32::to_bits), Some(value.to_bits()));
+        }
+        let nan = f64::from_bits(0xfff0_0000_dead_beef);
+        assert_eq!(
+            Float::from_f64(nan).to_f64().map(f64::to_bits),
+            Some(nan.to_bits())
+        );
+        assert_eq!(Float::from_f64(1.0e300).to_f32(), Some(f32::INFINITY));
+        assert_eq!(Float::from_f32(0.1).to_f64(), Some(f64::from(0.1f32)));
+    }
+
+    #[test]
+    fn rounds_to_narrow_formats() {
+        assert_eq!(half(1.0), 0x3c00);
+        assert_eq!(half(-2.0), 0xc000);
+        assert_eq!(half(65504.0), 0x7bff);
+        assert_eq!(half(65520.0), 0x7c00);
+        assert_eq!(half(1.0 + 2f64.powi(-11)), 0x3c00);
+        assert_eq!(half(1.0 + 3.0 * 2f64.powi(-11)), 0x3c02);
+        assert_eq!(half(2f64.powi(-24)), 0x0001);
+        assert_eq!(half(2f64.powi(-26)), 0x0000);
+
+        let zero = FloatStyle {
+            round: FloatRound::Zero,
+            denormalize: true,
+        };
+        let to_half = |value| Float::from_f64_with(value, HALF, zero).to_bits();
+        assert_eq!(to_half(1e6), Some(0x7bff));
+        assert_eq!(to_half(-1.999), Some(0xbffe));
+    }
+
+    #[test]
+    fn handles_denormals_explicitly() {
+        let flush = FloatStyle {
+            round: FloatRound::Nearest,
+            denormalize: false,
+        };
+        let tiny = Float::from_f32(-f32::from_bits(1));
+        assert_eq!(tiny.classify(), FloatClass::NegDenormal);
+        assert_eq!(tiny.to_f32(), Some(-f32::from_bits(1)));
+        assert_eq!(tiny.to_f32_with(flush).map(f32::to_bits), Some(0x8000_0000));
+        assert_eq!(
+            Float::from_f64_with(2f64.powi(-20), HALF, flush).classify(),
+            FloatClass::PosZero
+        );
+    }
+
+    #[test]
+    fn classifies_special_values() {
+        assert_eq!(Float::from_f32(f32::NAN).classify(), FloatClass::QuietNan);
+        let signalling = Float::from_f64(f64::from_bits(0x7ff0_0000_0000_0001));
+        assert_eq!(signalling.classify(), FloatClass::SignalingNan);
+        let narrowed = signalling.resize(HALF, FloatStyle::default()).unwrap();
+        assert_eq!(narrowed.classify(), FloatClass::SignalingNan);
+        assert_eq!(
+            Float::from_f32(f32::NEG_INFINITY).classify(),
+            FloatClass::NegInf
+        );
+        assert_eq!(Float::from_f32(-0.0).classify(), FloatClass::NegZero);
+        assert_eq!(Float::from_f32(2.5).classify(), FloatClass::PosNormal);
+
+        let bits = LogicVec::from("0011110000000X00");
+        let float = Float::new(bits, HALF).unwrap();
+        assert_eq!(float.classify(), FloatClass::Metavalue);
+        assert_eq!(float.to_f64(), None);
+        assert_eq!(float.to_string(), "0011110000000x00");
+        assert_eq!(
+            Float::new(LogicVec::from("0L11110000000000"), HALF)
+                .unwrap()
+                .to_f64(),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn derives_format_from_range() {
+        assert_eq!(
+            FloatFormat::from_range(8, -23).unwrap(),
+            FloatFormat::FLOAT32
+        );
+        assert_eq!(FloatFormat::from_range(5, -10).unwrap(), HALF);
+        assert!(FloatFormat::from_range(8, 0).is_err());
+        assert!(FloatFormat::from_range(15, -112).is_err());
+        assert!(Float::new(LogicVec::from("0101"), HALF).is_err());
+    }
+}
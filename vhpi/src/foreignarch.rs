@@ -0,0 +1,326 @@
+//! Architectures implemented in Rust via [`register_foreign_arch`].
+//!
+//! A foreign architecture replaces the body of a VHDL entity with a Rust
+//! model.  The simulator calls the model's elaboration and execution
+//! callbacks once per instance; this module turns those into a
+//! [`ForeignArchitecture`] value per instance, re-evaluates it whenever one
+//! of its input ports changes, and lets it drive its output ports through
+//! scheduled transactions.
+//!
+//! # Example
+//!
+//! ```vhdl
+//! entity xor_gate is
+//!     port (a, b : in bit; y : out bit);
+//! end entity;
+//!
+//! architecture rust of xor_gate is
+//!     attribute foreign of rust : architecture is "VHPI my_lib xor_gate";
+//! begin
+//! end architecture;
+//! ```
+//!
+//! ```rust,no_run
+//! use vhpi::{ConstantValue, Error, ForeignArchitecture, ForeignPorts, Value};
+//!
+//! struct XorGate;
+//!
+//! impl ForeignArchitecture for XorGate {
+//!     fn elaborate(_ports: &ForeignPorts, _generics: &[ConstantValue]) -> Result<Self, Error> {
+//!         Ok(XorGate)
+//!     }
+//!
+//!     fn evaluate(&mut self, ports: &ForeignPorts) -> Result<(), Error> {
+//!         let (Value::Enum(a), Value::Enum(b)) = (ports.read("a")?, ports.read("b")?) else {
+//!             return Err("unexpected port format".into());
+//!         };
+//!         ports.write("y", Value::Enum(a ^ b))
+//!     }
+//! }
+//!
+//! extern "C" fn register_xor_gate() {
+//!     vhpi::register_foreign_arch::<XorGate>("my_lib", "xor_gate")
+//!         .expect("failed to register foreign architecture `xor_gate`");
+//! }
+//!
+//! vhpi::startup_routines!(register_xor_gate);
+//! ```
+
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::mem::ManuallyDrop;
+
+use crate::{
+    register_foreignf, CbReason, ConstantValue, DelayMode, Error, ForeignData, ForeignExecData,
+    ForeignKind, Format, Handle, Mode, OneToMany, Severity, Time, Value,
+};
+
+/// A VHDL architecture implemented in Rust.
+///
+/// One value is created per instance of the entity by
+/// [`elaborate`](Self::elaborate).  [`evaluate`](Self::evaluate) is then
+/// called once when simulation starts and again after each value change on
+/// an `in` or `inout` port.
+pub trait ForeignArchitecture: Sized + 'static {
+    /// Create the model state for one instance.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error reports it through `vhpi_assert` and leaves the
+    /// instance without a model.
+    fn elaborate(ports: &ForeignPorts, generics: &[ConstantValue]) -> Result<Self, Error>;
+
+    /// Recompute the outputs after an input changed.
+    ///
+    /// # Errors
+    ///
+    /// Errors are reported through `vhpi_assert`; the model keeps running.
+    fn evaluate(&mut self, ports: &ForeignPorts) -> Result<(), Error>;
+}
+
+/// A port of a foreign architecture instance.
+#[derive(Debug)]
+pub struct ForeignPort {
+    /// Port name as reported by the simulator.
+    pub name: String,
+    /// Port mode, `None` if the simulator reports an unknown mode.
+    pub mode: Option<Mode>,
+    /// Handle to the port declaration.
+    pub handle: Handle,
+}
+
+impl ForeignPort {
+    fn is_input(&self) -> bool {
+        matches!(self.mode, Some(Mode::In | Mode::Inout))
+    }
+
+    fn is_output(&self) -> bool {
+        matches!(self.mode, Some(Mode::Out | Mode::Inout | Mode::Buffer))
+    }
+}
+
+/// The ports of a foreign architecture instance.
+#[derive(Debug)]
+pub struct ForeignPorts {
+    ports: Vec<ForeignPort>,
+}
+
+impl ForeignPorts {
+    fn new(instance: &Handle) -> Self {
+        let ports = instance
+            .iterator(OneToMany::PortDecls)
+            .map(|handle| ForeignPort {
+                name: handle.get_name().unwrap_or_default(),
+                mode: handle.get_mode(),
+                handle,
+            })
+            .collect();
+        Self { ports }
+    }
+
+    /// Iterate over all ports in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = &ForeignPort> {
+        self.ports.iter()
+    }
+
+    /// Find a port by name, ignoring case.
+    #[must_use]
+    pub fn port(&self, name: &str) -> Option<&ForeignPort> {
+        self.ports
+            .iter()
+            .find(|port| port.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Read the current value of the port `name` in its native format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there is no such port or the simulator rejects
+    /// the read.
+    pub fn read(&self, name: &str) -> Result<Value, Error> {
+        self.lookup(name)?.handle.get_value(Format::ObjType)
+    }
+
+    /// Drive `value` onto the output port `name` in the next delta cycle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there is no such port, the port has mode `in`,
+    /// or the simulator rejects the transaction.
+    pub fn write(&self, name: &str, value: Value) -> Result<(), Error> {
+        self.write_after(name, value, Time::from(0_i64))
+    }
+
+    /// Drive `value` onto the output port `name` after `delay`, using
+    /// inertial delay.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when there is no such port, the port has mode `in`,
+    /// or the simulator rejects the transaction.
+    pub fn write_after(&self, name: &str, value: Value, delay: Time) -> Result<(), Error> {
+        let port = self.lookup(name)?;
+        if !port.is_output() {
+            return Err(format!("port {} is not an output", port.name)
+                .as_str()
+                .into());
+        }
+        port.handle
+            .schedule_transaction(value, delay, DelayMode::Inertial)
+    }
+
+    fn lookup(&self, name: &str) -> Result<&ForeignPort, Error> {
+        self.port(name)
+            .ok_or_else(|| Error::from(format!("no port named {name}").as_str()))
+    }
+}
+
+/// State of one instance, leaked for the rest of the simulation.
+struct Instance<T> {
+    name: String,
+    ports: ForeignPorts,
+    model: RefCell<Option<T>>,
+    started: Cell<bool>,
+}
+
+impl<T: ForeignArchitecture> Instance<T> {
+    fn evaluate(&self) {
+        // A callback can only re-enter while the model is already running,
+        // in which case the outer call sees the new input values anyway.
+        let Ok(mut model) = self.model.try_borrow_mut() else {
+            return;
+        };
+        if let Some(model) = model.as_mut() {
+            if let Err(err) = model.evaluate(&self.ports) {
+                crate::assert(Severity::Error, format!("{}: {}", self.name, err.message));
+            }
+        }
+    }
+}
+
+/// A created instance, keyed by its instance handle and stored as the
+/// model's `TypeId` and a leaked `Box<Instance<T>>` pointer.
+///
+/// The handle belongs to the simulator, which keeps it alive for the whole
+/// design, so it is never released here.
+struct Entry {
+    obj: ManuallyDrop<Handle>,
+    type_id: TypeId,
+    ptr: usize,
+}
+
+thread_local! {
+    static INSTANCES: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Find the instance whose handle is `obj`, if it has been created.
+///
+/// The inner `None` means the instance belongs to a different model type.
+fn lookup<T: ForeignArchitecture>(obj: &Handle) -> Option<Option<&'static Instance<T>>> {
+    INSTANCES.with_borrow(|instances| {
+        let entry = instances.iter().find(|entry| *entry.obj == *obj)?;
+        if entry.type_id != TypeId::of::<T>() {
+            return Some(None);
+        }
+        // SAFETY: the pointer was created in `instance` from a
+        // `Box<Instance<T>>` with the same `TypeId` and is never freed.
+        Some(Some(unsafe { &*(entry.ptr as *const Instance<T>) }))
+    })
+}
+
+/// Return the state of the instance `data` refers to, creating it on first
+/// use.
+fn instance<T: ForeignArchitecture>(data: &ForeignExecData) -> Option<&'static Instance<T>> {
+    let obj = data.obj();
+    if obj.is_null() {
+        crate::assert(
+            Severity::Error,
+            "foreign architecture callback has no instance handle",
+        );
+        return None;
+    }
+    if let Some(found) = lookup::<T>(&obj) {
+        return found;
+    }
+
+    // The registry is not borrowed while user code runs, so `elaborate`
+    // may itself use VHPI callbacks that look up instances.
+    let name = obj.get_full_name().unwrap_or_default();
+    let ports = ForeignPorts::new(&obj);
+    let model = match T::elaborate(&ports, &obj.generics()) {
+        Ok(model) => Some(model),
+        Err(err) => {
+            crate::assert(Severity::Error, format!("{name}: {}", err.message));
+            None
+        }
+    };
+
+    if let Some(found) = lookup::<T>(&obj) {
+        return found;
+    }
+    let instance: &'static Instance<T> = Box::leak(Box::new(Instance {
+        name,
+        ports,
+        model: RefCell::new(model),
+        started: Cell::new(false),
+    }));
+    INSTANCES.with_borrow_mut(|instances| {
+        instances.push(Entry {
+            obj,
+            type_id: TypeId::of::<T>(),
+            ptr: std::ptr::from_ref(instance) as usize,
+        });
+    });
+    Some(instance)
+}
+
+unsafe extern "C" fn foreign_arch_elab<T: ForeignArchitecture>(data: &ForeignExecData) {
+    let _ = instance::<T>(data);
+}
+
+unsafe extern "C" fn foreign_arch_exec<T: ForeignArchitecture>(data: &ForeignExecData) {
+    let Some(instance) = instance::<T>(data) else {
+        return;
+    };
+    if instance.started.replace(true) {
+        return;
+    }
+
+    for port in instance.ports.iter().filter(|port| port.is_input()) {
+        let registered = port
+            .handle
+            .register_cb(CbReason::ValueChange, move |_| instance.evaluate());
+        if let Err(err) = registered {
+            crate::assert(
+                Severity::Error,
+                format!(
+                    "{}: cannot watch port {}: {err:?}",
+                    instance.name, port.name
+                ),
+            );
+        }
+    }
+
+    instance.evaluate();
+}
+
+/// Register `T` as the implementation of a foreign architecture.
+///
+/// `library_name` and `model_name` must match the VHDL `foreign` attribute
+/// of the architecture.  Call this from a startup routine, like
+/// [`register_foreignf`].  Each instance of the architecture gets its own
+/// `T`, so the same model may also be registered under several names.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if the simulator rejects the registration.
+pub fn register_foreign_arch<T: ForeignArchitecture>(
+    library_name: &str,
+    model_name: &str,
+) -> Result<Handle, Error> {
+    register_foreignf(
+        &ForeignData::new(ForeignKind::Arch, library_name, model_name)
+            .elab(foreign_arch_elab::<T>)
+            .exec(foreign_arch_exec::<T>),
+    )
+}
@@ -0,0 +1,318 @@
+//! Cross-checking VHDL `foreign` attributes against registered models.
+//!
+//! A misspelled library or model name in a `foreign` attribute is normally
+//! only reported when the subprogram is first called, which may be deep
+//! into a run.  [`check_foreign_bindings`] compares every
+//! `"VHPI <library> <model>"` attribute in the design with the models
+//! returned by [`foreign_models`] so that mismatches can be reported once
+//! elaboration has finished.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{CbReason, Severity};
+//!
+//! extern "C" fn check_startup() {
+//!     vhpi::register_cb(CbReason::EndOfElaboration, |_| {
+//!         for mismatch in vhpi::check_foreign_bindings() {
+//!             vhpi::assert(Severity::Error, mismatch.to_string());
+//!         }
+//!     })
+//!     .expect("failed to register end of elaboration callback");
+//! }
+//!
+//! vhpi::startup_routines!(check_startup);
+//! ```
+
+use std::fmt;
+
+use crate::{
+    handle, ClassKind, ForeignInfo, ForeignKind, Handle, OneToMany, OneToOne, SourceLocation,
+    StrProperty, Value,
+};
+
+/// A `foreign` attribute naming a VHPI model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignBinding {
+    /// Full name of the subprogram or design unit carrying the attribute.
+    pub name: String,
+    /// Kind of model the declaration expects.
+    pub kind: ForeignKind,
+    /// Library name from the attribute string.
+    pub library_name: String,
+    /// Model name from the attribute string.
+    pub model_name: String,
+    /// Location of the declaration in the VHDL source.
+    pub location: Option<SourceLocation>,
+}
+
+/// A `foreign` attribute without a matching registered model.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForeignMismatch {
+    /// No model is registered for the library.
+    UnknownLibrary(ForeignBinding),
+    /// The library has registered models, but none with this name.
+    UnknownModel(ForeignBinding),
+    /// The model is registered with a different kind, e.g. as a procedure
+    /// for a function declaration.
+    KindMismatch {
+        /// The attribute that was checked.
+        binding: ForeignBinding,
+        /// Kind the model was registered with.
+        registered: ForeignKind,
+    },
+}
+
+impl ForeignMismatch {
+    #[must_use]
+    /// The attribute that failed to match.
+    pub fn binding(&self) -> &ForeignBinding {
+        match self {
+            ForeignMismatch::UnknownLibrary(binding)
+            | ForeignMismatch::UnknownModel(binding)
+            | ForeignMismatch::KindMismatch { binding, .. } => binding,
+        }
+    }
+}
+
+impl fmt::Display for ForeignMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let binding = self.binding();
+        if let Some(location) = &binding.location {
+            write!(f, "{location}: ")?;
+        }
+        write!(
+            f,
+            "{} refers to foreign model \"VHPI {} {}\" ",
+            binding.name, binding.library_name, binding.model_name
+        )?;
+        match self {
+            ForeignMismatch::UnknownLibrary(_) => {
+                write!(f, "but no models are registered for that library")
+            }
+            ForeignMismatch::UnknownModel(_) => {
+                write!(f, "but no model of that name is registered")
+            }
+            ForeignMismatch::KindMismatch {
+                binding,
+                registered,
+            } => write!(
+                f,
+                "which is registered as {registered:?} instead of {:?}",
+                binding.kind
+            ),
+        }
+    }
+}
+
+/// Return every foreign model registered with the simulator.
+///
+/// Models whose registration record cannot be read are skipped.
+#[must_use]
+pub fn foreign_models() -> Vec<ForeignInfo> {
+    Handle::null()
+        .iterator(OneToMany::Foreignfs)
+        .filter_map(|model| model.get_foreignf_info().ok())
+        .collect()
+}
+
+/// Collect the VHPI `foreign` attributes in the elaborated design.
+///
+/// Subprogram declarations are searched in all package instances and in
+/// every region of the design hierarchy, along with the architectures of
+/// instantiated entities.  Attributes that do not start with `VHPI` are
+/// ignored.  Each library and model pair is reported once.
+#[must_use]
+pub fn foreign_bindings() -> Vec<ForeignBinding> {
+    let mut bindings = Vec::new();
+    for pack in Handle::null().iterator(OneToMany::PackInsts) {
+        collect_subprograms(&pack, &mut bindings);
+    }
+
+    let root = handle(OneToOne::RootInst);
+    if !root.is_null() {
+        collect_region(&root, &mut bindings);
+    }
+
+    bindings
+}
+
+/// Compare [`foreign_bindings`] with [`foreign_models`].
+///
+/// Call this once elaboration has finished, for example from a
+/// [`CbReason::EndOfElaboration`](crate::CbReason::EndOfElaboration)
+/// callback, so that all startup routines have registered their models.
+#[must_use]
+pub fn check_foreign_bindings() -> Vec<ForeignMismatch> {
+    match_bindings(foreign_bindings(), &foreign_models())
+}
+
+fn collect_region(region: &Handle, bindings: &mut Vec<ForeignBinding>) {
+    collect_subprograms(region, bindings);
+
+    let unit = region.handle(OneToOne::DesignUnit);
+    if unit.get_kind() == Some(ClassKind::ArchBody) {
+        let name = unit
+            .get_str(StrProperty::UnitName)
+            .or_else(|| unit.get_name())
+            .unwrap_or_default();
+        add_binding(&unit, name, ForeignKind::Arch, bindings);
+    }
+
+    for sub in region.iterator(OneToMany::InternalRegions) {
+        collect_region(&sub, bindings);
+    }
+}
+
+fn collect_subprograms(region: &Handle, bindings: &mut Vec<ForeignBinding>) {
+    for decl in region.iterator(OneToMany::Decls) {
+        let kind = match decl.get_kind() {
+            Some(ClassKind::FuncDecl) => ForeignKind::Func,
+            Some(ClassKind::ProcDecl) => ForeignKind::Proc,
+            _ => continue,
+        };
+        let name = decl
+            .get_full_name()
+            .or_else(|| decl.get_name())
+            .unwrap_or_default();
+        add_binding(&decl, name, kind, bindings);
+    }
+}
+
+fn add_binding(decl: &Handle, name: String, kind: ForeignKind, bindings: &mut Vec<ForeignBinding>) {
+    let Ok(Some(Value::Str(attr))) = decl.attribute("foreign") else {
+        return;
+    };
+    let Some((library_name, model_name)) = parse_foreign_attribute(&attr) else {
+        return;
+    };
+    if bindings
+        .iter()
+        .any(|b| b.library_name == library_name && b.model_name == model_name)
+    {
+        return;
+    }
+
+    bindings.push(ForeignBinding {
+        name,
+        kind,
+        library_name,
+        model_name,
+        location: decl.source_location(),
+    });
+}
+
+/// Split a `"VHPI <library> <model>"` attribute string into library and
+/// model name.
+fn parse_foreign_attribute(attr: &str) -> Option<(String, String)> {
+    let mut words = attr.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("VHPI") {
+        return None;
+    }
+    let library_name = words.next()?.to_string();
+    let model_name = words.next()?.to_string();
+    Some((library_name, model_name))
+}
+
+fn match_bindings(bindings: Vec<ForeignBinding>, models: &[ForeignInfo]) -> Vec<ForeignMismatch> {
+    bindings
+        .into_iter()
+        .filter_map(|binding| {
+            let mut library = models
+                .iter()
+                .filter(|m| m.library_name.as_deref() == Some(binding.library_name.as_str()))
+                .peekable();
+            if library.peek().is_none() {
+                return Some(ForeignMismatch::UnknownLibrary(binding));
+            }
+
+            let model =
+                library.find(|m| m.model_name.as_deref() == Some(binding.model_name.as_str()));
+            match model {
+                None => Some(ForeignMismatch::UnknownModel(binding)),
+                Some(model) if model.kind != binding.kind => Some(ForeignMismatch::KindMismatch {
+                    binding,
+                    registered: model.kind,
+                }),
+                Some(_) => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(kind: ForeignKind, library_name: &str, model_name: &str) -> ForeignInfo {
+        ForeignInfo {
+            kind,
+            library_name: Some(library_name.to_string()),
+            model_name: Some(model_name.to_string()),
+            elab: None,
+            exec: None,
+        }
+    }
+
+    fn binding(kind: ForeignKind, library_name: &str, model_name: &str) -> ForeignBinding {
+        ForeignBinding {
+            name: format!(":WORK:{model_name}"),
+            kind,
+            library_name: library_name.to_string(),
+            model_name: model_name.to_string(),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn parses_vhpi_attributes() {
+        assert_eq!(
+            parse_foreign_attribute("VHPI my_lib add_ints"),
+            Some(("my_lib".to_string(), "add_ints".to_string()))
+        );
+        assert_eq!(
+            parse_foreign_attribute("  vhpi  my_lib\tadd_ints "),
+            Some(("my_lib".to_string(), "add_ints".to_string()))
+        );
+        assert_eq!(parse_foreign_attribute("VHPI my_lib"), None);
+        assert_eq!(parse_foreign_attribute("GHDL my_lib add_ints"), None);
+        assert_eq!(parse_foreign_attribute(""), None);
+    }
+
+    #[test]
+    fn reports_each_kind_of_mismatch() {
+        let models = [
+            model(ForeignKind::Func, "my_lib", "add_ints"),
+            model(ForeignKind::Proc, "my_lib", "mark_call"),
+        ];
+        let bindings = vec![
+            binding(ForeignKind::Func, "my_lib", "add_ints"),
+            binding(ForeignKind::Func, "my_lb", "add_ints"),
+            binding(ForeignKind::Func, "my_lib", "add_int"),
+            binding(ForeignKind::Func, "my_lib", "mark_call"),
+        ];
+
+        let mismatches = match_bindings(bindings, &models);
+        assert_eq!(mismatches.len(), 3);
+        assert!(matches!(
+            &mismatches[0],
+            ForeignMismatch::UnknownLibrary(b) if b.library_name == "my_lb"
+        ));
+        assert!(matches!(
+            &mismatches[1],
+            ForeignMismatch::UnknownModel(b) if b.model_name == "add_int"
+        ));
+        assert!(matches!(
+            &mismatches[2],
+            ForeignMismatch::KindMismatch {
+                registered: ForeignKind::Proc,
+                ..
+            }
+        ));
+        assert_eq!(
+            mismatches[2].to_string(),
+            ":WORK:mark_call refers to foreign model \"VHPI my_lib mark_call\" \
+             which is registered as Proc instead of Func"
+        );
+    }
+}
@@ -75,7 +75,11 @@
 use std::ffi::{CStr, CString};
 use std::mem::ManuallyDrop;
 
-use crate::{check_error, Error, Format, Handle, LogicVal, OneToMany, Value};
+use crate::composite::put_composite_with;
+use crate::{
+    check_error, string_to_iso8859_1_cstring, ClassKind, CompositeValue, DelayMode, Error, Format,
+    Handle, Mode, OneToMany, PutValueMode, Time, Value,
+};
 
 /// The kind of VHPI foreign model, corresponding to `vhpiForeignKindT`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -165,6 +169,95 @@ impl ForeignExecData {
             .collect()
     }
 
+    /// Read a foreign subprogram argument by parameter name.
+    ///
+    /// The name is matched case-insensitively against the formal parameters
+    /// and the value is read in its native format.  Returns `None` when no
+    /// parameter has that name or the simulator rejects value retrieval.
+    #[must_use]
+    pub fn arg(&self, name: &str) -> Option<Value> {
+        self.param_by_name(name)?.get_value(Format::ObjType).ok()
+    }
+
+    /// Write a foreign procedure `out` or `inout` argument by index.
+    ///
+    /// Variable parameters are updated immediately.  Signal parameters get a
+    /// transaction scheduled for the next delta cycle, the same as a signal
+    /// assignment in the procedure body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the index is out of range, the parameter has
+    /// mode `in`, is not a variable or signal, or the simulator rejects the
+    /// write.
+    pub fn set_foreignf_arg(&self, index: u32, value: Value) -> Result<(), Error> {
+        write_param(&self.param(index)?, CompositeValue::Scalar(value))
+    }
+
+    /// Write a foreign procedure `out` or `inout` argument by parameter name.
+    ///
+    /// See [`set_foreignf_arg`](Self::set_foreignf_arg) for how the value is
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when no parameter has that name, or under the same
+    /// conditions as [`set_foreignf_arg`](Self::set_foreignf_arg).
+    pub fn set_arg(&self, name: &str, value: Value) -> Result<(), Error> {
+        let param = self
+            .param_by_name(name)
+            .ok_or_else(|| Error::from(format!("no parameter named {name}").as_str()))?;
+        write_param(&param, CompositeValue::Scalar(value))
+    }
+
+    /// Read a record or array foreign subprogram argument by index.
+    ///
+    /// Scalar parameters are returned as [`CompositeValue::Scalar`], so this
+    /// works for any parameter type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the index is out of range or any element of the
+    /// parameter cannot be read.
+    pub fn get_foreignf_composite_arg(&self, index: u32) -> Result<CompositeValue, Error> {
+        self.param(index)?.get_composite_value()
+    }
+
+    /// Write a record or array foreign procedure `out` or `inout` argument
+    /// by index.
+    ///
+    /// Each element is written as described for
+    /// [`set_foreignf_arg`](Self::set_foreignf_arg).  Record fields missing
+    /// from `value` keep their current value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as
+    /// [`set_foreignf_arg`](Self::set_foreignf_arg), or when `value` names a
+    /// record field or array index the parameter does not have.
+    pub fn set_foreignf_composite_arg(
+        &self,
+        index: u32,
+        value: CompositeValue,
+    ) -> Result<(), Error> {
+        write_param(&self.param(index)?, value)
+    }
+
+    fn param(&self, index: u32) -> Result<Handle, Error> {
+        i32::try_from(index)
+            .ok()
+            .and_then(|index| self.obj().handle_by_index(OneToMany::ParamDecls, index))
+            .ok_or_else(|| Error::from(format!("no parameter at index {index}").as_str()))
+    }
+
+    fn param_by_name(&self, name: &str) -> Option<Handle> {
+        self.obj().iterator(OneToMany::ParamDecls).find(|param| {
+            param
+                .get_name()
+                .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        })
+    }
+
     /// Return `true` when the simulator provided a writable return-value buffer.
     ///
     /// This is most commonly available for foreign functions. For procedures,
@@ -202,7 +295,8 @@ impl ForeignExecData {
     ///
     /// This path is simulator-dependent. Some simulators do not provide a writable
     /// buffer for certain return types (for example unconstrained vectors).
-    /// Callers should be prepared to fall back to other mechanisms.
+    /// Use [`put_return_value`](Self::put_return_value) to fall back to other
+    /// mechanisms automatically.
     ///
     /// Supported value kinds currently are:
     /// - `Value::Int`
@@ -210,16 +304,25 @@ impl ForeignExecData {
     /// - `Value::Real`
     /// - `Value::Char`
     /// - `Value::Logic`
-    /// - `Value::LogicVec`
+    /// - `Value::Enum` and `Value::SmallEnum`
+    /// - `Value::Time`
+    /// - `Value::Str`
+    /// - `Value::LogicVec`, `Value::IntVec`, `Value::RealVec` and `Value::EnumVec`
     ///
     /// # Errors
     ///
     /// Returns an error when the simulator does not provide a value buffer, the
     /// format/size is incompatible with `value`, or the value kind is unsupported.
     pub fn try_put_return_value(&self, value: Value) -> Result<(), Error> {
+        self.write_return_buffer(value).map_err(Error::from)
+    }
+
+    fn write_return_buffer(&self, value: Value) -> Result<(), BufferError> {
         let raw = self.0.value;
         if raw.is_null() {
-            return Err("foreignf: simulator did not provide return-value buffer".into());
+            return Err(BufferError::Unavailable(
+                "foreignf: simulator did not provide return-value buffer".into(),
+            ));
         }
 
         // SAFETY: `raw` is checked non-null above and points to the callback-owned
@@ -229,107 +332,278 @@ impl ForeignExecData {
 
         match value {
             Value::Int(v) => {
-                if !matches!(format, Format::Int | Format::ObjType) {
-                    return Err(format!(
-                        "foreignf: return-value format mismatch for Int: got {format:?}"
-                    )
-                    .as_str()
-                    .into());
-                }
+                check_return_format(format, Format::Int, "Int")?;
                 raw.value.intg = v;
                 Ok(())
             }
             Value::LongInt(v) => {
-                if !matches!(format, Format::LongInt | Format::ObjType) {
-                    return Err(format!(
-                        "foreignf: return-value format mismatch for LongInt: got {format:?}"
-                    )
-                    .as_str()
-                    .into());
-                }
+                check_return_format(format, Format::LongInt, "LongInt")?;
                 raw.value.longintg = v;
                 Ok(())
             }
             Value::Real(v) => {
-                if !matches!(format, Format::Real | Format::ObjType) {
-                    return Err(format!(
-                        "foreignf: return-value format mismatch for Real: got {format:?}"
-                    )
-                    .as_str()
-                    .into());
-                }
+                check_return_format(format, Format::Real, "Real")?;
                 raw.value.real = v;
                 Ok(())
             }
             Value::Char(v) => {
-                if !matches!(format, Format::Char | Format::ObjType) {
-                    return Err(format!(
-                        "foreignf: return-value format mismatch for Char: got {format:?}"
-                    )
-                    .as_str()
-                    .into());
-                }
+                check_return_format(format, Format::Char, "Char")?;
                 raw.value.ch = v as u8;
                 Ok(())
             }
             Value::Logic(v) => {
-                if !matches!(format, Format::Logic | Format::ObjType) {
-                    return Err(format!(
-                        "foreignf: return-value format mismatch for Logic: got {format:?}"
-                    )
-                    .as_str()
-                    .into());
-                }
+                check_return_format(format, Format::Logic, "Logic")?;
                 raw.value.enumv = v.into();
                 Ok(())
             }
-            Value::LogicVec(values) => {
-                if !matches!(format, Format::LogicVec | Format::ObjType) {
-                    return Err(format!(
-                        "foreignf: return-value format mismatch for LogicVec: got {format:?}"
-                    )
-                    .as_str()
-                    .into());
+            Value::Enum(v) => {
+                check_return_format(format, Format::Enum, "Enum")?;
+                raw.value.enumv = v;
+                Ok(())
+            }
+            Value::SmallEnum(v) => {
+                check_return_format(format, Format::SmallEnum, "SmallEnum")?;
+                raw.value.smallenumv = v;
+                Ok(())
+            }
+            Value::Time(v) => {
+                check_return_format(format, Format::Time, "Time")?;
+                raw.value.time = v.into();
+                Ok(())
+            }
+            Value::Str(v) => {
+                check_return_format(format, Format::Str, "Str")?;
+                let c_string = string_to_iso8859_1_cstring(v);
+                let bytes = c_string.as_bytes_with_nul();
+
+                // SAFETY: Accessing a union field is unsafe; for Str we expect
+                // `str_` to be the active field.
+                let out_ptr = unsafe { raw.value.str_ };
+                if out_ptr.is_null() {
+                    return Err(BufferError::Unavailable(
+                        "foreignf: simulator provided null return string buffer pointer".into(),
+                    ));
                 }
-
-                if raw.numElems < 0 {
-                    return Err("foreignf: simulator reported negative return vector length".into());
+                if raw.bufSize < bytes.len() {
+                    return Err(BufferError::Unavailable(
+                        format!(
+                            "foreignf: return string of {} characters does not fit buffer of {} bytes",
+                            bytes.len() - 1,
+                            raw.bufSize
+                        )
+                        .as_str()
+                        .into(),
+                    ));
                 }
 
-                let expected_len = raw.numElems as usize;
-                if expected_len != values.len() {
-                    return Err(format!(
-                        "foreignf: return vector length mismatch: simulator expects {}, got {}",
-                        expected_len,
-                        values.len()
+                // SAFETY: `str_` points to a simulator-owned buffer of `bufSize`
+                // bytes for this callback.
+                let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, bytes.len()) };
+                out.copy_from_slice(bytes);
+                raw.numElems = (bytes.len() - 1) as i32;
+                Ok(())
+            }
+            Value::LogicVec(values) => {
+                check_return_format(format, Format::LogicVec, "LogicVec")?;
+                let values: Vec<vhpi_sys::vhpiEnumT> = values.iter().map(|v| (*v).into()).collect();
+                // SAFETY: for LogicVec we expect `enumvs` to be the active field.
+                write_return_vec(raw, unsafe { raw.value.enumvs }, &values)
+            }
+            Value::EnumVec(values) => {
+                check_return_format(format, Format::EnumVec, "EnumVec")?;
+                // SAFETY: for EnumVec we expect `enumvs` to be the active field.
+                write_return_vec(raw, unsafe { raw.value.enumvs }, &values)
+            }
+            Value::IntVec(values) => {
+                check_return_format(format, Format::IntVec, "IntVec")?;
+                // SAFETY: for IntVec we expect `intgs` to be the active field.
+                write_return_vec(raw, unsafe { raw.value.intgs }, &values)
+            }
+            Value::RealVec(values) => {
+                check_return_format(format, Format::RealVec, "RealVec")?;
+                // SAFETY: for RealVec we expect `reals` to be the active field.
+                write_return_vec(raw, unsafe { raw.value.reals }, &values)
+            }
+            other => Err(BufferError::Unavailable(
+                format!(
+                    "foreignf: unsupported return-value kind for direct buffer write: {other:?}"
+                )
+                .as_str()
+                .into(),
+            )),
+        }
+    }
+
+    /// Write a return value, falling back to the function handle when needed.
+    ///
+    /// Tries [`try_put_return_value`](Self::try_put_return_value) first.  If
+    /// the simulator did not provide a buffer, or one too small for the
+    /// value, for example for an unconstrained `string` or
+    /// `std_logic_vector` result, the value is written with
+    /// [`put_return_value_by_handle`](Self::put_return_value_by_handle)
+    /// instead.  Value kinds with no direct buffer support also take the
+    /// by-handle path.  Returns the path that was used.
+    ///
+    /// # Errors
+    ///
+    /// Returns the buffer error when the buffer exists but does not match
+    /// the value, for example a format mismatch, and the by-handle error
+    /// when the fallback write is rejected.
+    pub fn put_return_value(&self, value: Value) -> Result<ReturnPath, Error> {
+        match self.write_return_buffer(value.clone()) {
+            Ok(()) => Ok(ReturnPath::Buffer),
+            Err(BufferError::Unavailable(_)) => self
+                .put_return_value_by_handle(value)
+                .map(|()| ReturnPath::Handle),
+            Err(BufferError::Rejected(err)) => Err(err),
+        }
+    }
+
+    /// Write a return value with `vhpi_put_value` on the function handle.
+    ///
+    /// This bypasses the callback value buffer entirely and lets the simulator
+    /// size the result from `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the simulator rejects the value write.
+    pub fn put_return_value_by_handle(&self, value: Value) -> Result<(), Error> {
+        self.obj().put_value(value, PutValueMode::Deposit)
+    }
+
+    /// Write a record return value field by field.
+    ///
+    /// VHPI has no value format for records, so each entry of `fields` is
+    /// written in declaration order to the matching element of the
+    /// function's `SelectedNames`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the simulator does not expose the record
+    /// elements of the result or rejects a field value.
+    pub fn put_return_fields(&self, fields: impl IntoIterator<Item = Value>) -> Result<(), Error> {
+        let func = self.obj();
+        for (index, value) in fields.into_iter().enumerate() {
+            let field = i32::try_from(index)
+                .ok()
+                .and_then(|index| func.handle_by_index(OneToMany::SelectedNames, index))
+                .ok_or_else(|| {
+                    Error::from(
+                        format!("foreignf: return value has no record element {index}").as_str(),
                     )
-                    .as_str()
-                    .into());
-                }
+                })?;
+            field.put_value(value, PutValueMode::Deposit)?;
+        }
+        Ok(())
+    }
+}
 
-                // SAFETY: Accessing a union field is unsafe; for LogicVec we
-                // expect `enumvs` to be the active field.
-                let out_ptr = unsafe { raw.value.enumvs };
-                if out_ptr.is_null() {
-                    return Err(
-                        "foreignf: simulator provided null return vector buffer pointer".into(),
-                    );
-                }
+fn check_return_format(format: Format, expected: Format, kind: &str) -> Result<(), Error> {
+    if format == expected || format == Format::ObjType {
+        Ok(())
+    } else {
+        Err(
+            format!("foreignf: return-value format mismatch for {kind}: got {format:?}")
+                .as_str()
+                .into(),
+        )
+    }
+}
 
-                // SAFETY: `enumvs` points to a simulator-owned buffer with at least
-                // `numElems` elements for this callback.
-                let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, expected_len) };
-                for (dst, src) in out.iter_mut().zip(values.iter()) {
-                    *dst = <vhpi_sys::vhpiEnumT as From<LogicVal>>::from(*src);
-                }
-                Ok(())
-            }
-            other => Err(format!(
-                "foreignf: unsupported return-value kind for direct buffer write: {other:?}"
+/// How [`ForeignExecData::put_return_value`] delivered a return value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnPath {
+    /// Written into the simulator-provided return-value buffer.
+    Buffer,
+    /// Written with `vhpi_put_value` on the function handle.
+    Handle,
+}
+
+/// Why a return value could not be written into the callback buffer.
+enum BufferError {
+    /// There is no usable buffer, or it is too small for the value, so the
+    /// value may still be written through the function handle.
+    Unavailable(Error),
+    /// The buffer does not match the value.
+    Rejected(Error),
+}
+
+impl From<Error> for BufferError {
+    fn from(err: Error) -> Self {
+        BufferError::Rejected(err)
+    }
+}
+
+impl From<BufferError> for Error {
+    fn from(err: BufferError) -> Self {
+        match err {
+            BufferError::Unavailable(err) | BufferError::Rejected(err) => err,
+        }
+    }
+}
+
+/// Copy `values` into a simulator-provided return vector buffer.
+fn write_return_vec<T: Copy>(
+    raw: &vhpi_sys::vhpiValueT,
+    out_ptr: *mut T,
+    values: &[T],
+) -> Result<(), BufferError> {
+    let Ok(expected_len) = usize::try_from(raw.numElems) else {
+        return Err(BufferError::Unavailable(
+            "foreignf: simulator reported negative return vector length".into(),
+        ));
+    };
+
+    if expected_len != values.len() {
+        let err = format!(
+            "foreignf: return vector length mismatch: simulator expects {}, got {}",
+            expected_len,
+            values.len()
+        )
+        .as_str()
+        .into();
+        return Err(if expected_len < values.len() {
+            BufferError::Unavailable(err)
+        } else {
+            BufferError::Rejected(err)
+        });
+    }
+
+    if out_ptr.is_null() {
+        return Err(BufferError::Unavailable(
+            "foreignf: simulator provided null return vector buffer pointer".into(),
+        ));
+    }
+
+    // SAFETY: the buffer holds at least `numElems` elements for this callback.
+    let out = unsafe { std::slice::from_raw_parts_mut(out_ptr, expected_len) };
+    out.copy_from_slice(values);
+    Ok(())
+}
+
+/// Write `value` to the formal parameter `param` using the put mechanism
+/// matching its class.
+fn write_param(param: &Handle, value: CompositeValue) -> Result<(), Error> {
+    let name = param.get_name().unwrap_or_default();
+    match param.get_mode() {
+        Some(Mode::Out | Mode::Inout) => {}
+        mode => {
+            return Err(
+                format!("parameter {name} has mode {mode:?}, expected out or inout")
+                    .as_str()
+                    .into(),
             )
+        }
+    }
+
+    match param.get_kind() {
+        Some(ClassKind::VarParamDecl) => param.put_composite_value(value, PutValueMode::Deposit),
+        Some(ClassKind::SigParamDecl) => put_composite_with(param, value, &mut |obj, value| {
+            obj.schedule_transaction(value, Time::from(0_i64), DelayMode::Inertial)
+        }),
+        kind => Err(format!("cannot write parameter {name} of class {kind:?}")
             .as_str()
             .into()),
-        }
     }
 }
 
@@ -0,0 +1,440 @@
+//! Safe closure-based foreign subprograms via [`register_foreign_fn`].
+//!
+//! [`register_foreignf`] requires a hand-written `unsafe extern "C"` callback
+//! that reads each parameter with [`ForeignExecData::get_foreignf_arg`].  This
+//! module generates that callback instead: parameters are converted with
+//! [`ForeignArg`] and the result is written back with [`ForeignReturn`].
+//! Closures returning `()` are registered as procedures, all others as
+//! functions.  Conversion failures, and `Err` values returned from the
+//! closure, are reported through `vhpi_assert` with error severity.
+//!
+//! # Example
+//!
+//! ```vhdl
+//! function add(a, b : integer) return integer;
+//! attribute foreign of add : function is "VHPI my_lib add";
+//! ```
+//!
+//! ```rust,no_run
+//! extern "C" fn register_add() {
+//!     vhpi::register_foreign_fn("my_lib", "add", |a: i32, b: i32| a + b)
+//!         .expect("failed to register foreign function `add`");
+//! }
+//!
+//! vhpi::startup_routines!(register_add);
+//! ```
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{
+    register_foreignf, CompositeValue, Error, ForeignComposite, ForeignData, ForeignExecData,
+    ForeignKind, Format, Handle, LogicVal, LogicVec, Time, Value,
+};
+
+/// Conversion from a foreign subprogram parameter to a Rust value.
+pub trait ForeignArg: Sized {
+    /// Format used to read the parameter.
+    const FORMAT: Format;
+
+    /// Convert the value read from the simulator, or return `None` if it has
+    /// an unexpected kind.
+    fn from_value(value: Value) -> Option<Self>;
+
+    /// Read the parameter at `index`.
+    ///
+    /// The default reads a single value in [`FORMAT`](Self::FORMAT).
+    /// Composite types override this to read the parameter element by
+    /// element.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameter cannot be read or converted.
+    fn read(data: &ForeignExecData, index: u32) -> Result<Self, Error> {
+        data.get_foreignf_arg(index, Self::FORMAT)
+            .and_then(Self::from_value)
+            .ok_or_else(|| {
+                format!(
+                    "cannot read argument {index} as {:?} ({})",
+                    Self::FORMAT,
+                    std::any::type_name::<Self>()
+                )
+                .as_str()
+                .into()
+            })
+    }
+}
+
+/// Conversion from a Rust value to a foreign subprogram `out` or `inout`
+/// parameter.
+///
+/// Implemented for every [`ForeignArg`] that is also a
+/// [`ForeignComposite`].
+pub trait ForeignOut: ForeignArg + ForeignComposite {}
+
+impl<T: ForeignArg + ForeignComposite> ForeignOut for T {}
+
+/// Conversion from a Rust value to the result of a foreign subprogram.
+pub trait ForeignReturn {
+    /// Kind of foreign model to register for closures returning this type.
+    const KIND: ForeignKind;
+
+    /// Write the result back to the simulator.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the simulator rejects the value.
+    fn put(self, data: &ForeignExecData) -> Result<(), Error>;
+}
+
+macro_rules! impl_foreign_arg {
+    ($ty:ty, $format:ident, $variant:ident) => {
+        impl ForeignArg for $ty {
+            const FORMAT: Format = Format::$format;
+
+            fn from_value(value: Value) -> Option<Self> {
+                match value {
+                    Value::$variant(v) => Some(v),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_foreign_arg!(i32, Int, Int);
+impl_foreign_arg!(i64, LongInt, LongInt);
+impl_foreign_arg!(f64, Real, Real);
+impl_foreign_arg!(char, Char, Char);
+impl_foreign_arg!(LogicVal, Logic, Logic);
+impl_foreign_arg!(LogicVec, LogicVec, LogicVec);
+impl_foreign_arg!(String, Str, Str);
+impl_foreign_arg!(Time, Time, Time);
+
+impl ForeignArg for bool {
+    const FORMAT: Format = Format::Enum;
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Enum(n) => Some(n != 0),
+            Value::SmallEnum(n) => Some(n != 0),
+            _ => None,
+        }
+    }
+}
+
+impl<T: ForeignComposite> ForeignArg for Vec<T> {
+    const FORMAT: Format = Format::ObjType;
+
+    fn from_value(value: Value) -> Option<Self> {
+        Self::from_composite(CompositeValue::Scalar(value))
+    }
+
+    fn read(data: &ForeignExecData, index: u32) -> Result<Self, Error> {
+        __private::read_composite(data, index)
+    }
+}
+
+impl ForeignArg for CompositeValue {
+    const FORMAT: Format = Format::ObjType;
+
+    fn from_value(value: Value) -> Option<Self> {
+        Some(CompositeValue::Scalar(value))
+    }
+
+    fn read(data: &ForeignExecData, index: u32) -> Result<Self, Error> {
+        data.get_foreignf_composite_arg(index)
+    }
+}
+
+impl ForeignReturn for () {
+    const KIND: ForeignKind = ForeignKind::Proc;
+
+    fn put(self, _data: &ForeignExecData) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_foreign_return {
+    ($ty:ty, $variant:ident) => {
+        impl ForeignReturn for $ty {
+            const KIND: ForeignKind = ForeignKind::Func;
+
+            fn put(self, data: &ForeignExecData) -> Result<(), Error> {
+                data.put_return_value(Value::$variant(self)).map(drop)
+            }
+        }
+    };
+}
+
+impl_foreign_return!(i32, Int);
+impl_foreign_return!(i64, LongInt);
+impl_foreign_return!(f64, Real);
+impl_foreign_return!(char, Char);
+impl_foreign_return!(LogicVal, Logic);
+impl_foreign_return!(LogicVec, LogicVec);
+impl_foreign_return!(String, Str);
+impl_foreign_return!(Vec<i32>, IntVec);
+impl_foreign_return!(Vec<f64>, RealVec);
+impl_foreign_return!(Time, Time);
+
+impl ForeignReturn for bool {
+    const KIND: ForeignKind = ForeignKind::Func;
+
+    fn put(self, data: &ForeignExecData) -> Result<(), Error> {
+        data.put_return_value(Value::Enum(u32::from(self)))
+            .map(drop)
+    }
+}
+
+impl<T, E> ForeignReturn for Result<T, E>
+where
+    T: ForeignReturn,
+    E: std::fmt::Display,
+{
+    const KIND: ForeignKind = T::KIND;
+
+    fn put(self, data: &ForeignExecData) -> Result<(), Error> {
+        match self {
+            Ok(value) => value.put(data),
+            Err(err) => Err(err.to_string().as_str().into()),
+        }
+    }
+}
+
+/// A Rust callable that can be registered with [`register_foreign_fn`].
+///
+/// Implemented for closures and functions taking up to eight [`ForeignArg`]
+/// parameters and returning a [`ForeignReturn`] value.  `Args` is the tuple
+/// of parameter types and only serves to select the implementation.
+pub trait ForeignFn<Args>: 'static {
+    /// Result type of the callable.
+    type Output: ForeignReturn;
+
+    /// Read the parameters from `data` and invoke the callable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a parameter cannot be read or converted.
+    fn call(&self, data: &ForeignExecData) -> Result<Self::Output, Error>;
+}
+
+fn arg<A: ForeignArg>(data: &ForeignExecData, index: u32) -> Result<A, Error> {
+    A::read(data, index)
+}
+
+macro_rules! impl_foreign_fn {
+    ($($arg:ident $index:literal),*) => {
+        impl<Func, Ret, $($arg),*> ForeignFn<($($arg,)*)> for Func
+        where
+            Func: Fn($($arg),*) -> Ret + 'static,
+            Ret: ForeignReturn,
+            $($arg: ForeignArg,)*
+        {
+            type Output = Ret;
+
+            #[allow(unused_variables)]
+            fn call(&self, data: &ForeignExecData) -> Result<Ret, Error> {
+                Ok(self($(arg::<$arg>(data, $index)?),*))
+            }
+        }
+    };
+}
+
+impl_foreign_fn!();
+impl_foreign_fn!(A0 0);
+impl_foreign_fn!(A0 0, A1 1);
+impl_foreign_fn!(A0 0, A1 1, A2 2);
+impl_foreign_fn!(A0 0, A1 1, A2 2, A3 3);
+impl_foreign_fn!(A0 0, A1 1, A2 2, A3 3, A4 4);
+impl_foreign_fn!(A0 0, A1 1, A2 2, A3 3, A4 4, A5 5);
+impl_foreign_fn!(A0 0, A1 1, A2 2, A3 3, A4 4, A5 5, A6 6);
+impl_foreign_fn!(A0 0, A1 1, A2 2, A3 3, A4 4, A5 5, A6 6, A7 7);
+
+thread_local! {
+    /// Registered callables keyed by their type, stored as leaked `Box`
+    /// pointers.
+    ///
+    /// The simulator does not pass user data to foreign model callbacks, so
+    /// each callable type gets its own trampoline which looks up its
+    /// instance here.  Callables need not be `Sync`, so each is only
+    /// reachable from the simulator thread that registered it.
+    static CALLABLES: RefCell<HashMap<TypeId, usize>> = RefCell::new(HashMap::new());
+}
+
+unsafe extern "C" fn foreign_fn_trampoline<F, Args>(data: &ForeignExecData)
+where
+    F: ForeignFn<Args>,
+{
+    let ptr = CALLABLES.with_borrow(|callables| callables.get(&TypeId::of::<F>()).copied());
+    let Some(ptr) = ptr else {
+        return;
+    };
+
+    // SAFETY: the pointer was created from a `Box<F>` in `register_foreign_fn`
+    // and is never freed.
+    let callable = &*(ptr as *const F);
+
+    // A panic must not unwind into the simulator.
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        callable.call(data).and_then(|out| out.put(data))
+    }))
+    .unwrap_or_else(|payload| Err(panic_message(payload.as_ref()).as_str().into()));
+    if let Err(err) = result {
+        __private::report(data, &err);
+    }
+}
+
+/// Describe the payload of a caught panic.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic payload");
+    format!("panicked: {message}")
+}
+
+/// Register a Rust closure as the implementation of a foreign function or
+/// procedure.
+///
+/// `library_name` and `model_name` must match the VHDL `foreign` attribute.
+/// Call this from a startup routine, like [`register_foreignf`].  The closure
+/// is kept alive for the rest of the simulation and, as it need not be
+/// `Send` or `Sync`, is only called on the registering thread.  A panic in
+/// the closure is reported as a simulation error rather than unwinding into
+/// the simulator.
+///
+/// Each registration needs a distinct closure type: registering the same
+/// capturing closure twice would make both models share the first one's
+/// state and is rejected.  Functions and non-capturing closures may be
+/// registered under several names.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if the closure type is already registered or the
+/// simulator rejects the registration.
+pub fn register_foreign_fn<F, Args>(
+    library_name: &str,
+    model_name: &str,
+    f: F,
+) -> Result<Handle, Error>
+where
+    F: ForeignFn<Args>,
+{
+    CALLABLES.with_borrow_mut(|callables| match callables.entry(TypeId::of::<F>()) {
+        Entry::Occupied(_) if size_of::<F>() != 0 => Err(Error::from(
+            format!("closure for `{model_name}` is already registered as another foreign model")
+                .as_str(),
+        )),
+        Entry::Occupied(_) => Ok(()),
+        Entry::Vacant(entry) => {
+            entry.insert(Box::into_raw(Box::new(f)) as usize);
+            Ok(())
+        }
+    })?;
+
+    register_foreignf(
+        &ForeignData::new(F::Output::KIND, library_name, model_name)
+            .exec(foreign_fn_trampoline::<F, Args>),
+    )
+}
+
+/// Support functions for code generated by the `#[foreign]` attribute macro.
+#[doc(hidden)]
+pub mod __private {
+    use crate::{CompositeValue, Error, ForeignComposite, ForeignExecData, Severity};
+
+    use super::{ForeignArg, ForeignOut};
+
+    pub fn read_arg<A: ForeignArg>(data: &ForeignExecData, index: u32) -> Result<A, Error> {
+        super::arg(data, index)
+    }
+
+    pub fn write_arg<A: ForeignOut>(
+        data: &ForeignExecData,
+        index: u32,
+        value: A,
+    ) -> Result<(), Error> {
+        data.set_foreignf_composite_arg(index, value.into_composite())
+    }
+
+    pub fn read_composite<T: ForeignComposite>(
+        data: &ForeignExecData,
+        index: u32,
+    ) -> Result<T, Error> {
+        let value = data.get_foreignf_composite_arg(index)?;
+        T::from_composite(value).ok_or_else(|| {
+            format!(
+                "cannot read argument {index} as {}",
+                std::any::type_name::<T>()
+            )
+            .as_str()
+            .into()
+        })
+    }
+
+    /// Remove the record field `name` from `fields` and convert it.
+    pub fn take_field<T: ForeignComposite>(
+        fields: &mut Vec<(String, CompositeValue)>,
+        name: &str,
+    ) -> Option<T> {
+        let position = fields
+            .iter()
+            .position(|(field, _)| field.eq_ignore_ascii_case(name))?;
+        T::from_composite(fields.swap_remove(position).1)
+    }
+
+    pub fn report(data: &ForeignExecData, err: &Error) {
+        let name = data.obj().get_name().unwrap_or_default();
+        crate::assert(Severity::Error, format!("{name}: {}", err.message));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_message_reads_string_payloads() {
+        assert_eq!(panic_message(&"boom"), "panicked: boom");
+        assert_eq!(
+            panic_message(&String::from("bad input")),
+            "panicked: bad input"
+        );
+        assert_eq!(panic_message(&42), "panicked: unknown panic payload");
+    }
+
+    #[test]
+    fn scalar_args_match_value_kind() {
+        assert_eq!(i32::from_value(Value::Int(5)), Some(5));
+        assert_eq!(i32::from_value(Value::LongInt(5)), None);
+        assert_eq!(f64::from_value(Value::Real(1.5)), Some(1.5));
+        assert_eq!(char::from_value(Value::Char('x')), Some('x'));
+    }
+
+    #[test]
+    fn bool_arg_accepts_enum_formats() {
+        assert_eq!(bool::from_value(Value::Enum(1)), Some(true));
+        assert_eq!(bool::from_value(Value::SmallEnum(0)), Some(false));
+        assert_eq!(bool::from_value(Value::Int(1)), None);
+    }
+
+    #[test]
+    fn return_kind_follows_output_type() {
+        fn kind_of<F: ForeignFn<Args>, Args>(_f: F) -> ForeignKind {
+            F::Output::KIND
+        }
+
+        assert_eq!(kind_of(|_: i32| {}), ForeignKind::Proc);
+        assert_eq!(kind_of(|a: i32, b: i32| a + b), ForeignKind::Func);
+        assert_eq!(
+            kind_of(|s: String| s.parse::<i32>().map_err(|e| e.to_string())),
+            ForeignKind::Func
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Library and application models via [`register_foreign_library`].
+//!
+//! A [`ForeignLibrary`] holds state shared by all the foreign subprograms a
+//! plugin exports.  It is created when the library is registered, notified
+//! when elaboration finishes, and shut down at the end of simulation.  In
+//! between, foreign subprograms borrow it with [`with_library`] instead of
+//! keeping their own globals.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{Error, ForeignKind, ForeignLibrary};
+//!
+//! #[derive(Default)]
+//! struct Counters {
+//!     calls: usize,
+//! }
+//!
+//! impl ForeignLibrary for Counters {
+//!     fn on_load() -> Result<Self, Error> {
+//!         Ok(Counters::default())
+//!     }
+//!
+//!     fn on_shutdown(&mut self) {
+//!         vhpi::printf!("{} calls", self.calls);
+//!     }
+//! }
+//!
+//! extern "C" fn my_lib_startup() {
+//!     vhpi::register_foreign_library::<Counters>(ForeignKind::Lib, "my_lib", "counters")
+//!         .expect("failed to register foreign library");
+//!
+//!     vhpi::register_foreign_fn("my_lib", "add", |a: i32, b: i32| {
+//!         vhpi::with_library(|counters: &mut Counters| counters.calls += 1)?;
+//!         Ok::<_, Error>(a + b)
+//!     })
+//!     .expect("failed to register foreign function `add`");
+//! }
+//!
+//! vhpi::startup_routines!(my_lib_startup);
+//! ```
+
+use std::any::TypeId;
+use std::cell::{Cell, RefCell, RefMut};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::{
+    register_cb, register_foreignf, CbReason, Error, ForeignData, ForeignExecData, ForeignKind,
+    Handle, RegisterCbError, Severity,
+};
+
+/// State shared by the foreign subprograms of a library.
+pub trait ForeignLibrary: Sized + 'static {
+    /// Create the library context.  Called once from
+    /// [`register_foreign_library`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned from [`register_foreign_library`] and the
+    /// library is not registered.
+    fn on_load() -> Result<Self, Error>;
+
+    /// Called once when elaboration of the design has finished.
+    fn on_elaboration(&mut self) {}
+
+    /// Called once at the end of simulation.
+    fn on_shutdown(&mut self) {}
+}
+
+struct Library<L> {
+    context: RefCell<L>,
+    elaborated: Cell<bool>,
+}
+
+impl<L: ForeignLibrary> Library<L> {
+    fn elaborate(&self) {
+        if self.elaborated.get() {
+            return;
+        }
+        if let Some(mut context) = self.context_for("on_elaboration") {
+            self.elaborated.set(true);
+            context.on_elaboration();
+        }
+    }
+
+    fn shutdown(&self) {
+        if let Some(mut context) = self.context_for("on_shutdown") {
+            context.on_shutdown();
+        }
+    }
+
+    /// Borrow the context to run `hook` from a simulator callback.
+    ///
+    /// A panic must not unwind into the simulator, so a context that is
+    /// still borrowed, for example by a [`with_library`] call that is
+    /// running when the callback fires, is reported through `vhpi_assert`
+    /// and the hook is skipped.
+    fn context_for(&self, hook: &str) -> Option<RefMut<'_, L>> {
+        match self.context.try_borrow_mut() {
+            Ok(context) => Some(context),
+            Err(_) => {
+                crate::assert(
+                    Severity::Error,
+                    format!(
+                        "{}: cannot run {hook} while the library context is borrowed",
+                        std::any::type_name::<L>()
+                    ),
+                );
+                None
+            }
+        }
+    }
+}
+
+thread_local! {
+    /// Registered libraries keyed by their type, stored as leaked
+    /// `Box<Library<L>>` pointers.
+    ///
+    /// The contexts are not `Sync`, so each is only reachable from the
+    /// simulator thread that registered it.
+    static LIBRARIES: RefCell<HashMap<TypeId, usize>> = RefCell::new(HashMap::new());
+}
+
+fn library<L: ForeignLibrary>() -> Option<&'static Library<L>> {
+    let ptr = LIBRARIES.with_borrow(|libraries| libraries.get(&TypeId::of::<L>()).copied())?;
+    // SAFETY: the pointer was created from a `Box<Library<L>>` in
+    // `register_foreign_library` and is never freed.
+    Some(unsafe { &*(ptr as *const Library<L>) })
+}
+
+unsafe extern "C" fn foreign_lib_elab<L: ForeignLibrary>(_data: &ForeignExecData) {
+    if let Some(library) = library::<L>() {
+        library.elaborate();
+    }
+}
+
+fn cb_error(err: RegisterCbError) -> Error {
+    match err {
+        RegisterCbError::Error(err) => err,
+        RegisterCbError::UnknownReason => "unknown callback reason".into(),
+    }
+}
+
+/// Register `L` as a library or application model and create its context.
+///
+/// `kind` must be [`ForeignKind::Lib`] or [`ForeignKind::App`].  Call this
+/// from a startup routine before registering the foreign subprograms that
+/// use the context.  [`ForeignLibrary::on_load`] runs immediately,
+/// [`ForeignLibrary::on_elaboration`] when the simulator elaborates the
+/// model or at the end of elaboration, whichever comes first, and
+/// [`ForeignLibrary::on_shutdown`] at the end of simulation.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if `kind` is not a library or application kind,
+/// `L` is already registered, `on_load` fails, or the simulator rejects
+/// the registration.
+pub fn register_foreign_library<L: ForeignLibrary>(
+    kind: ForeignKind,
+    library_name: &str,
+    model_name: &str,
+) -> Result<Handle, Error> {
+    if !matches!(kind, ForeignKind::Lib | ForeignKind::App) {
+        return Err(
+            format!("{kind:?} is not a library or application model kind")
+                .as_str()
+                .into(),
+        );
+    }
+
+    let already_registered = || {
+        Error::from(format!("library context for `{model_name}` is already registered").as_str())
+    };
+    // Checked up front so that `on_load` does not run for a duplicate.
+    if library::<L>().is_some() {
+        return Err(already_registered());
+    }
+
+    // Create the context before borrowing the registry so that `on_load`
+    // may use other registered libraries.  The insert checks again in case
+    // `on_load` registered `L` itself.
+    let context = L::on_load()?;
+    let library: &'static Library<L> =
+        LIBRARIES.with_borrow_mut(|libraries| match libraries.entry(TypeId::of::<L>()) {
+            Entry::Occupied(_) => Err(already_registered()),
+            Entry::Vacant(entry) => {
+                let library = Box::leak(Box::new(Library {
+                    context: RefCell::new(context),
+                    elaborated: Cell::new(false),
+                }));
+                entry.insert(std::ptr::from_ref(library) as usize);
+                Ok(library)
+            }
+        })?;
+
+    register_cb(CbReason::EndOfElaboration, move |_| library.elaborate()).map_err(cb_error)?;
+    register_cb(CbReason::EndOfSimulation, move |_| library.shutdown()).map_err(cb_error)?;
+
+    register_foreignf(&ForeignData::new(kind, library_name, model_name).elab(foreign_lib_elab::<L>))
+}
+
+/// Borrow the context of the registered library `L`.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if `L` has not been registered with
+/// [`register_foreign_library`] on this thread, or its context is already
+/// borrowed by an enclosing call.
+pub fn with_library<L: ForeignLibrary, R>(f: impl FnOnce(&mut L) -> R) -> Result<R, Error> {
+    let library = library::<L>().ok_or_else(|| {
+        Error::from(format!("{} is not registered", std::any::type_name::<L>()).as_str())
+    })?;
+    let mut context = library.context.try_borrow_mut().map_err(|_| {
+        Error::from(format!("{} is already borrowed", std::any::type_name::<L>()).as_str())
+    })?;
+    Ok(f(&mut context))
+}
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::{ClassKind, Error, Format, Handle, IntProperty, OneToMany, OneToOne, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Value of a generic or constant declared in a region.
+///
+/// The declared type is summarised by name and class rather than kept as a
+/// [`Handle`], so the result can be stored and cloned after the callback
+/// that read it returns.  For the full type model, iterate
+/// [`OneToMany::GenericDecls`] or [`OneToMany::ConstDecls`] and follow
+/// [`OneToOne::Type`] from the declaration.
+pub struct ConstantValue {
+    /// Simple name of the declaration.
+    pub name: String,
+    /// Current value, read with [`Format::ObjType`].
+    ///
+    /// Holds the error when the simulator cannot read the value, for
+    /// example for record or access types, so that one such declaration
+    /// does not hide the others.
+    pub value: Result<Value, Error>,
+    /// Name of the declared type, if the simulator reports one.
+    pub type_name: Option<String>,
+    /// Class of the declared type, e.g. [`ClassKind::IntTypeDecl`].
+    pub type_kind: Option<ClassKind>,
+    /// Enumeration literal for enum-typed values such as `boolean`.
+    pub literal: Option<String>,
+    /// Whether a generic took its default value rather than an actual from
+    /// the instantiating generic map.  Always `false` for constants.
+    pub is_default: bool,
+}
+
+impl ConstantValue {
+    fn read(decl: &Handle, is_default: bool) -> Self {
+        let name = decl.get_name().unwrap_or_default();
+        let value = decl.get_value(Format::ObjType);
+
+        let typ = decl.handle(OneToOne::Type);
+        let (type_name, type_kind, literal) = if typ.is_null() {
+            (None, None, None)
+        } else {
+            let literal = value
+                .as_ref()
+                .ok()
+                .and_then(|value| enum_literal(&typ, value));
+            (typ.get_name(), typ.get_kind(), literal)
+        };
+
+        Self {
+            name,
+            value,
+            type_name,
+            type_kind,
+            literal,
+            is_default,
+        }
+    }
+}
+
+/// Decide whether the generic `name` took its default value, given the
+/// lower-case formal names of the generic map if the simulator reports one.
+fn defaulted_by_assoc(associated: Option<&HashSet<String>>, name: &str) -> bool {
+    associated.is_some_and(|formals| !formals.contains(&name.to_ascii_lowercase()))
+}
+
+/// Look up the enumeration literal named by an enum-formatted `value` of type
+/// `typ`.
+pub(crate) fn enum_literal(typ: &Handle, value: &Value) -> Option<String> {
+    let pos = match value {
+        Value::Enum(n) => *n as usize,
+        Value::SmallEnum(n) => usize::from(*n),
+        _ => return None,
+    };
+    typ.enum_literals()?.into_iter().nth(pos)
+}
+
+impl Handle {
+    /// Read the generics of this region instance.
+    ///
+    /// A generic is reported as defaulted when the simulator marks it with
+    /// `vhpiIsDefaultP`, or when generic associations are available and none
+    /// of them names it as formal.
+    ///
+    /// Every declaration is returned; a generic whose value cannot be read
+    /// carries the error in [`ConstantValue::value`].
+    #[must_use]
+    pub fn generics(&self) -> Vec<ConstantValue> {
+        let mut associated: Option<HashSet<String>> = None;
+        for assoc in self.iterator(OneToMany::GenericAssocs) {
+            let formals = associated.get_or_insert_with(HashSet::new);
+            if assoc.get(IntProperty::IsDefault) == 1 {
+                continue;
+            }
+            if let Some(name) = assoc.handle(OneToOne::Formal).get_name() {
+                formals.insert(name.to_ascii_lowercase());
+            }
+        }
+
+        self.iterator(OneToMany::GenericDecls)
+            .map(|decl| {
+                let by_assoc = decl
+                    .get_name()
+                    .is_some_and(|name| defaulted_by_assoc(associated.as_ref(), &name));
+                let is_default = decl.get(IntProperty::IsDefault) == 1 || by_assoc;
+                ConstantValue::read(&decl, is_default)
+            })
+            .collect()
+    }
+
+    /// Read the constants declared in this region.
+    ///
+    /// Every declaration is returned; a constant whose value cannot be read
+    /// carries the error in [`ConstantValue::value`].
+    #[must_use]
+    pub fn constants(&self) -> Vec<ConstantValue> {
+        self.iterator(OneToMany::ConstDecls)
+            .map(|decl| ConstantValue::read(&decl, false))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generics_missing_from_the_map_are_defaulted() {
+        let formals = HashSet::from(["width".to_string()]);
+
+        assert!(!defaulted_by_assoc(Some(&formals), "WIDTH"));
+        assert!(defaulted_by_assoc(Some(&formals), "depth"));
+    }
+
+    #[test]
+    fn no_generic_map_means_no_default_by_assoc() {
+        assert!(!defaulted_by_assoc(None, "depth"));
+    }
+}
@@ -5,6 +5,7 @@
 //! | `nvc`     | Yes     | Include NVC-specific VHPI-extensions |
 //! | `bigint`  | No      | Functions that return `BigInt`/`BigUint` |
 //! | `dynamic` | No      | Enable runtime name resolution. |
+//! | `macros`  | No      | `#[foreign]` and `#[derive(ForeignComposite)]` macros |
 //!
 //! The `dynamic` feature is required if you want to build a dynamic library (dylib on macOS and DLL on Windows).
 //! If you link directly to the simulator it is not required.
@@ -13,30 +14,72 @@
 #[macro_use]
 mod macros;
 
+mod attribute;
+mod batch;
 mod callback;
+mod callinfo;
+mod composite;
+mod connectivity;
 mod control;
 mod error;
+mod fixed;
+mod float;
+mod foreignarch;
+mod foreigncheck;
 mod foreignf;
+mod foreignfn;
+mod foreignlib;
+mod generic;
 mod handle;
+mod location;
 mod logic;
+mod numeric;
+mod packed;
+mod parse;
 mod physical;
+mod process;
 mod property;
+mod reader;
 mod simulator;
+mod stack;
 mod time;
 mod value;
 
+pub use attribute::*;
+pub use batch::*;
 pub use callback::*;
+pub use callinfo::*;
+pub use composite::*;
+pub use connectivity::*;
 pub use control::*;
 pub use error::*;
+pub use fixed::*;
+pub use float::*;
+pub use foreignarch::*;
+pub use foreigncheck::*;
 pub use foreignf::*;
+pub use foreignfn::*;
+pub use foreignlib::*;
+pub use generic::*;
 pub use handle::*;
+pub use location::*;
 pub use logic::*;
+pub use numeric::*;
+pub use packed::*;
+pub use parse::*;
 pub use physical::*;
+pub use process::*;
 pub use property::*;
+pub use reader::*;
 pub use simulator::*;
+pub use stack::*;
 pub use time::*;
 pub use value::*;
 
+#[cfg(feature = "macros")]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+pub use vhpi_macros::{foreign, ForeignComposite};
+
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
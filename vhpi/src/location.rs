@@ -0,0 +1,131 @@
+use std::fmt;
+
+use crate::{Error, Handle, IntProperty, OneToOne, Severity, StrProperty};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Position of a VHDL construct in its source file.
+pub struct SourceLocation {
+    /// Path of the source file as reported by the simulator.
+    pub file: String,
+    /// First line of the construct.
+    pub begin_line: u32,
+    /// Last line of the construct, equal to `begin_line` when the simulator
+    /// only reports a single line.
+    pub end_line: u32,
+    /// Name of the design unit containing the construct, if known.
+    pub design_unit: Option<String>,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.begin_line)
+    }
+}
+
+fn line_number(raw: i32) -> Option<u32> {
+    u32::try_from(raw).ok().filter(|&line| line > 0)
+}
+
+impl Handle {
+    #[must_use]
+    /// Get the source location of the construct this handle refers to.
+    ///
+    /// Returns `None` if the simulator does not report a file name or line
+    /// number for the object.
+    pub fn source_location(&self) -> Option<SourceLocation> {
+        if self.is_null() {
+            return None;
+        }
+
+        let file = self.get_str(StrProperty::FileName)?;
+        let begin_line = line_number(self.get(IntProperty::BeginLineNo))
+            .or_else(|| line_number(self.get(IntProperty::LineNo)))?;
+        let end_line = line_number(self.get(IntProperty::EndLineNo))
+            .filter(|&end| end >= begin_line)
+            .unwrap_or(begin_line);
+
+        Some(SourceLocation {
+            file,
+            begin_line,
+            end_line,
+            design_unit: self.design_unit_name(),
+        })
+    }
+
+    /// Name of the design unit containing this object.
+    fn design_unit_name(&self) -> Option<String> {
+        let mut unit = self.handle(OneToOne::DesignUnit);
+        if unit.is_null() {
+            // Declarations and statements reach their unit through the
+            // enclosing region
+            let parent = self.handle(OneToOne::Parent);
+            if parent.is_null() {
+                return None;
+            }
+            unit = parent.handle(OneToOne::DesignUnit);
+        }
+
+        if unit.is_null() {
+            None
+        } else {
+            unit.get_str(StrProperty::UnitName)
+                .or_else(|| unit.get_name())
+        }
+    }
+}
+
+impl Error {
+    #[must_use]
+    /// Source location the simulator attached to this error.
+    ///
+    /// Returns `None` if the error does not carry a file name and line.
+    pub fn source_location(&self) -> Option<SourceLocation> {
+        let line = line_number(self.line?)?;
+        Some(SourceLocation {
+            file: self.file.clone()?,
+            begin_line: line,
+            end_line: line,
+            design_unit: None,
+        })
+    }
+}
+
+/// Emit a simulator assertion message prefixed with a source location.
+pub fn assert_at(severity: Severity, location: &SourceLocation, message: impl AsRef<str>) {
+    crate::assert(severity, format!("{location}: {}", message.as_ref()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_uses_first_line() {
+        let loc = SourceLocation {
+            file: "tb.vhd".to_string(),
+            begin_line: 12,
+            end_line: 20,
+            design_unit: Some("WORK.TB-TEST".to_string()),
+        };
+        assert_eq!(loc.to_string(), "tb.vhd:12");
+    }
+
+    #[test]
+    fn error_source_location() {
+        let mut err = Error {
+            severity: Severity::Error,
+            message: "bad".to_string(),
+            file: Some("top.vhd".to_string()),
+            line: Some(7),
+            context: None,
+        };
+        let loc = err.source_location().unwrap();
+        assert_eq!(loc.file, "top.vhd");
+        assert_eq!((loc.begin_line, loc.end_line), (7, 7));
+
+        err.line = Some(-1);
+        assert_eq!(err.source_location(), None);
+
+        assert_eq!(Error::from("no location").source_location(), None);
+    }
+}
@@ -135,7 +135,7 @@ impl fmt::Display for LogicVal {
 /// `LogicVec` is used to represent scalar and vector values made up of
 /// [`LogicVal`] elements. Integer constructors and conversions interpret the
 /// first element as the most significant bit.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct LogicVec {
     data: Vec<LogicVal>,
 }
@@ -295,6 +295,17 @@ impl LogicVec {
         Self { data: logic_data }
     }
 
+    /// Replaces the contents with raw VHPI enum values, reusing the
+    /// existing allocation.
+    pub(crate) fn assign_slice(&mut self, slice: &[u32]) {
+        self.data.clear();
+        self.data.extend(
+            slice
+                .iter()
+                .map(|&val| u8::try_from(val).map_or(LogicVal::X, LogicVal::from)),
+        );
+    }
+
     /// Wraps this logic vector as a [`Value::LogicVec`].
     #[must_use]
     pub fn as_value(&self) -> Value {
@@ -445,6 +456,452 @@ impl From<&String> for LogicVec {
     }
 }
 
+use LogicVal::{DontCare as D, One as I, Zero as O, H, L, U, W, X, Z};
+
+/// IEEE 1164 tables are indexed by the position of a value in the
+/// `std_ulogic` enumeration, which is the declaration order of [`LogicVal`].
+type Table = [[LogicVal; 9]; 9];
+
+#[rustfmt::skip]
+const RESOLUTION_TABLE: Table = [
+    // U  X  0  1  Z  W  L  H  -
+    [U, U, U, U, U, U, U, U, U], // U
+    [U, X, X, X, X, X, X, X, X], // X
+    [U, X, O, X, O, O, O, O, X], // 0
+    [U, X, X, I, I, I, I, I, X], // 1
+    [U, X, O, I, Z, W, L, H, X], // Z
+    [U, X, O, I, W, W, W, W, X], // W
+    [U, X, O, I, L, W, L, W, X], // L
+    [U, X, O, I, H, W, W, H, X], // H
+    [U, X, X, X, X, X, X, X, X], // -
+];
+
+#[rustfmt::skip]
+const AND_TABLE: Table = [
+    // U  X  0  1  Z  W  L  H  -
+    [U, U, O, U, U, U, O, U, U], // U
+    [U, X, O, X, X, X, O, X, X], // X
+    [O, O, O, O, O, O, O, O, O], // 0
+    [U, X, O, I, X, X, O, I, X], // 1
+    [U, X, O, X, X, X, O, X, X], // Z
+    [U, X, O, X, X, X, O, X, X], // W
+    [O, O, O, O, O, O, O, O, O], // L
+    [U, X, O, I, X, X, O, I, X], // H
+    [U, X, O, X, X, X, O, X, X], // -
+];
+
+#[rustfmt::skip]
+const OR_TABLE: Table = [
+    // U  X  0  1  Z  W  L  H  -
+    [U, U, U, I, U, U, U, I, U], // U
+    [U, X, X, I, X, X, X, I, X], // X
+    [U, X, O, I, X, X, O, I, X], // 0
+    [I, I, I, I, I, I, I, I, I], // 1
+    [U, X, X, I, X, X, X, I, X], // Z
+    [U, X, X, I, X, X, X, I, X], // W
+    [U, X, O, I, X, X, O, I, X], // L
+    [I, I, I, I, I, I, I, I, I], // H
+    [U, X, X, I, X, X, X, I, X], // -
+];
+
+#[rustfmt::skip]
+const XOR_TABLE: Table = [
+    // U  X  0  1  Z  W  L  H  -
+    [U, U, U, U, U, U, U, U, U], // U
+    [U, X, X, X, X, X, X, X, X], // X
+    [U, X, O, I, X, X, O, I, X], // 0
+    [U, X, I, O, X, X, I, O, X], // 1
+    [U, X, X, X, X, X, X, X, X], // Z
+    [U, X, X, X, X, X, X, X, X], // W
+    [U, X, O, I, X, X, O, I, X], // L
+    [U, X, I, O, X, X, I, O, X], // H
+    [U, X, X, X, X, X, X, X, X], // -
+];
+
+#[rustfmt::skip]
+const NOT_TABLE: [LogicVal; 9] = [
+    // U  X  0  1  Z  W  L  H  -
+    U, X, I, O, X, X, I, O, X,
+];
+
+impl LogicVal {
+    fn lookup(table: &Table, l: LogicVal, r: LogicVal) -> LogicVal {
+        table[l as usize][r as usize]
+    }
+
+    /// Resolve the values of several drivers as the `std_logic` resolution
+    /// function does.
+    ///
+    /// No drivers resolve to `Z` and a single driver to its own value.
+    #[must_use]
+    pub fn resolved(drivers: &[LogicVal]) -> LogicVal {
+        match drivers {
+            [single] => *single,
+            _ => drivers
+                .iter()
+                .fold(Z, |acc, &d| Self::lookup(&RESOLUTION_TABLE, acc, d)),
+        }
+    }
+
+    /// `nand` from IEEE 1164.
+    #[must_use]
+    pub fn nand(self, other: LogicVal) -> LogicVal {
+        !(self & other)
+    }
+
+    /// `nor` from IEEE 1164.
+    #[must_use]
+    pub fn nor(self, other: LogicVal) -> LogicVal {
+        !(self | other)
+    }
+
+    /// `xnor` from IEEE 1164.
+    #[must_use]
+    pub fn xnor(self, other: LogicVal) -> LogicVal {
+        !(self ^ other)
+    }
+
+    /// Strength strip to `X`, `0` or `1`, as `To_X01`.
+    #[must_use]
+    pub fn to_x01(self) -> LogicVal {
+        match self {
+            O | L => O,
+            I | H => I,
+            U | X | Z | W | D => X,
+        }
+    }
+
+    /// Strength strip to `X`, `0`, `1` or `Z`, as `To_X01Z`.
+    #[must_use]
+    pub fn to_x01z(self) -> LogicVal {
+        match self {
+            Z => Z,
+            _ => self.to_x01(),
+        }
+    }
+
+    /// Strength strip to `U`, `X`, `0` or `1`, as `To_UX01`.
+    #[must_use]
+    pub fn to_ux01(self) -> LogicVal {
+        match self {
+            U => U,
+            _ => self.to_x01(),
+        }
+    }
+
+    /// `true` for values that are neither a strong nor a weak `0` or `1`,
+    /// as `Is_X`.
+    #[must_use]
+    pub fn is_x(self) -> bool {
+        matches!(self, U | X | Z | W | D)
+    }
+
+    /// Compare two values treating `-` as matching anything, as
+    /// `std_match` from `numeric_std`.
+    ///
+    /// Weak and strong values of the same level match; metavalues never do.
+    #[must_use]
+    pub fn std_match(self, other: LogicVal) -> bool {
+        matches!(
+            (self, other),
+            (D, _) | (_, D) | (O | L, O | L) | (I | H, I | H)
+        )
+    }
+
+    /// `true` if a signal changing from `prev` to `self` is a rising edge,
+    /// as `rising_edge` evaluates it.
+    #[must_use]
+    pub fn is_rising_edge_from(self, prev: LogicVal) -> bool {
+        prev.to_x01() == O && self.to_x01() == I
+    }
+
+    /// `true` if a signal changing from `prev` to `self` is a falling edge,
+    /// as `falling_edge` evaluates it.
+    #[must_use]
+    pub fn is_falling_edge_from(self, prev: LogicVal) -> bool {
+        prev.to_x01() == I && self.to_x01() == O
+    }
+}
+
+impl std::ops::Not for LogicVal {
+    type Output = LogicVal;
+
+    fn not(self) -> LogicVal {
+        NOT_TABLE[self as usize]
+    }
+}
+
+macro_rules! impl_logic_val_binop {
+    ($($trait:ident, $method:ident, $table:ident;)+) => {
+        $(
+            impl std::ops::$trait for LogicVal {
+                type Output = LogicVal;
+
+                fn $method(self, other: LogicVal) -> LogicVal {
+                    LogicVal::lookup(&$table, self, other)
+                }
+            }
+        )+
+    };
+}
+
+impl_logic_val_binop! {
+    BitAnd, bitand, AND_TABLE;
+    BitOr, bitor, OR_TABLE;
+    BitXor, bitxor, XOR_TABLE;
+}
+
+impl LogicVec {
+    fn map(&self, f: impl Fn(LogicVal) -> LogicVal) -> Self {
+        self.data.iter().map(|&v| f(v)).collect()
+    }
+
+    fn zip_with(
+        &self,
+        other: &LogicVec,
+        op: &str,
+        f: impl Fn(LogicVal, LogicVal) -> LogicVal,
+    ) -> Self {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "arguments of {op} have different lengths"
+        );
+        self.data
+            .iter()
+            .zip(&other.data)
+            .map(|(&l, &r)| f(l, r))
+            .collect()
+    }
+
+    /// Returns the element at `index` in stored order.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<LogicVal> {
+        self.data.get(index).copied()
+    }
+
+    /// Returns a copy of the elements in `range`, in stored order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    #[must_use]
+    pub fn slice(&self, range: impl std::ops::RangeBounds<usize>) -> Self {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        Self::new(&self.data[range])
+    }
+
+    /// Returns `self` followed by `other`, as the VHDL `&` operator.
+    #[must_use]
+    pub fn concat(&self, other: &LogicVec) -> Self {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    /// Resolve the vectors driven onto a `std_logic_vector` element by
+    /// element with [`LogicVal::resolved`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the drivers have different lengths.
+    #[must_use]
+    pub fn resolved(drivers: &[LogicVec]) -> Self {
+        let Some(first) = drivers.first() else {
+            return Self::new([]);
+        };
+        (0..first.len())
+            .map(|i| {
+                let column: Vec<LogicVal> = drivers
+                    .iter()
+                    .map(|d| {
+                        assert_eq!(d.len(), first.len(), "drivers have different lengths");
+                        d.data[i]
+                    })
+                    .collect();
+                LogicVal::resolved(&column)
+            })
+            .collect()
+    }
+
+    /// `nand` of each pair of elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors have different lengths.
+    #[must_use]
+    pub fn nand(&self, other: &LogicVec) -> Self {
+        self.zip_with(other, "nand", LogicVal::nand)
+    }
+
+    /// `nor` of each pair of elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors have different lengths.
+    #[must_use]
+    pub fn nor(&self, other: &LogicVec) -> Self {
+        self.zip_with(other, "nor", LogicVal::nor)
+    }
+
+    /// `xnor` of each pair of elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vectors have different lengths.
+    #[must_use]
+    pub fn xnor(&self, other: &LogicVec) -> Self {
+        self.zip_with(other, "xnor", LogicVal::xnor)
+    }
+
+    /// Applies [`LogicVal::to_x01`] to each element.
+    #[must_use]
+    pub fn to_x01(&self) -> Self {
+        self.map(LogicVal::to_x01)
+    }
+
+    /// Applies [`LogicVal::to_x01z`] to each element.
+    #[must_use]
+    pub fn to_x01z(&self) -> Self {
+        self.map(LogicVal::to_x01z)
+    }
+
+    /// Applies [`LogicVal::to_ux01`] to each element.
+    #[must_use]
+    pub fn to_ux01(&self) -> Self {
+        self.map(LogicVal::to_ux01)
+    }
+
+    /// `true` if any element is a metavalue, as `Is_X`.
+    #[must_use]
+    pub fn is_x(&self) -> bool {
+        self.data.iter().any(|v| v.is_x())
+    }
+
+    /// Compare two vectors element by element with [`LogicVal::std_match`].
+    ///
+    /// Vectors of different lengths never match.
+    #[must_use]
+    pub fn std_match(&self, other: &LogicVec) -> bool {
+        self.len() == other.len()
+            && self
+                .data
+                .iter()
+                .zip(&other.data)
+                .all(|(l, r)| l.std_match(*r))
+    }
+}
+
+impl std::ops::Index<usize> for LogicVec {
+    type Output = LogicVal;
+
+    fn index(&self, index: usize) -> &LogicVal {
+        &self.data[index]
+    }
+}
+
+impl FromIterator<LogicVal> for LogicVec {
+    fn from_iter<T: IntoIterator<Item = LogicVal>>(iter: T) -> Self {
+        Self {
+            data: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<LogicVal> for LogicVec {
+    fn extend<T: IntoIterator<Item = LogicVal>>(&mut self, iter: T) {
+        self.data.extend(iter);
+    }
+}
+
+impl std::ops::Not for &LogicVec {
+    type Output = LogicVec;
+
+    fn not(self) -> LogicVec {
+        self.map(|v| !v)
+    }
+}
+
+impl std::ops::Not for LogicVec {
+    type Output = LogicVec;
+
+    fn not(self) -> LogicVec {
+        !&self
+    }
+}
+
+macro_rules! impl_logic_vec_binop {
+    ($($trait:ident, $method:ident, $op:literal;)+) => {
+        $(
+            /// # Panics
+            ///
+            /// Panics if the vectors have different lengths.
+            impl std::ops::$trait for &LogicVec {
+                type Output = LogicVec;
+
+                fn $method(self, other: &LogicVec) -> LogicVec {
+                    self.zip_with(other, $op, std::ops::$trait::$method)
+                }
+            }
+
+            impl std::ops::$trait for LogicVec {
+                type Output = LogicVec;
+
+                fn $method(self, other: LogicVec) -> LogicVec {
+                    std::ops::$trait::$method(&self, &other)
+                }
+            }
+        )+
+    };
+}
+
+impl_logic_vec_binop! {
+    BitAnd, bitand, "and";
+    BitOr, bitor, "or";
+    BitXor, bitxor, "xor";
+}
+
+/// Logical shift towards the first element, filling with `0` as `sll`.
+impl std::ops::Shl<usize> for &LogicVec {
+    type Output = LogicVec;
+
+    fn shl(self, amount: usize) -> LogicVec {
+        let amount = amount.min(self.len());
+        self.data[amount..]
+            .iter()
+            .copied()
+            .chain(std::iter::repeat_n(O, amount))
+            .collect()
+    }
+}
+
+/// Logical shift towards the last element, filling with `0` as `srl`.
+impl std::ops::Shr<usize> for &LogicVec {
+    type Output = LogicVec;
+
+    fn shr(self, amount: usize) -> LogicVec {
+        let amount = amount.min(self.len());
+        let kept = self.len().saturating_sub(amount);
+        std::iter::repeat_n(O, amount)
+            .chain(self.data[..kept].iter().copied())
+            .collect()
+    }
+}
+
+impl std::ops::Shl<usize> for LogicVec {
+    type Output = LogicVec;
+
+    fn shl(self, amount: usize) -> LogicVec {
+        &self << amount
+    }
+}
+
+impl std::ops::Shr<usize> for LogicVec {
+    type Output = LogicVec;
+
+    fn shr(self, amount: usize) -> LogicVec {
+        &self >> amount
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{LogicVal, LogicVec, LogicVecToIntError};
@@ -770,4 +1227,104 @@ mod tests {
             &[LogicVal::One, LogicVal::One, LogicVal::Zero, LogicVal::One]
         );
     }
+
+    fn vec(s: &str) -> LogicVec {
+        LogicVec::from(s)
+    }
+
+    #[test]
+    fn logic_val_operators_follow_ieee_1164_tables() {
+        assert_eq!(LogicVal::U & LogicVal::Zero, LogicVal::Zero);
+        assert_eq!(LogicVal::U & LogicVal::X, LogicVal::U);
+        assert_eq!(LogicVal::H & LogicVal::One, LogicVal::One);
+        assert_eq!(LogicVal::Z & LogicVal::One, LogicVal::X);
+        assert_eq!(LogicVal::X | LogicVal::H, LogicVal::One);
+        assert_eq!(LogicVal::L | LogicVal::Zero, LogicVal::Zero);
+        assert_eq!(LogicVal::W | LogicVal::U, LogicVal::U);
+        assert_eq!(LogicVal::H ^ LogicVal::One, LogicVal::Zero);
+        assert_eq!(LogicVal::U ^ LogicVal::X, LogicVal::U);
+        assert_eq!(!LogicVal::L, LogicVal::One);
+        assert_eq!(!LogicVal::Z, LogicVal::X);
+        assert_eq!(LogicVal::One.nand(LogicVal::H), LogicVal::Zero);
+        assert_eq!(LogicVal::Zero.nor(LogicVal::L), LogicVal::One);
+        assert_eq!(LogicVal::One.xnor(LogicVal::L), LogicVal::Zero);
+    }
+
+    #[test]
+    fn logic_val_resolution_matches_std_logic() {
+        assert_eq!(LogicVal::resolved(&[]), LogicVal::Z);
+        assert_eq!(
+            LogicVal::resolved(&[LogicVal::DontCare]),
+            LogicVal::DontCare
+        );
+        assert_eq!(LogicVal::resolved(&[LogicVal::Z, LogicVal::H]), LogicVal::H);
+        assert_eq!(LogicVal::resolved(&[LogicVal::L, LogicVal::H]), LogicVal::W);
+        assert_eq!(
+            LogicVal::resolved(&[LogicVal::Zero, LogicVal::H, LogicVal::Z]),
+            LogicVal::Zero
+        );
+        assert_eq!(
+            LogicVal::resolved(&[LogicVal::Zero, LogicVal::One]),
+            LogicVal::X
+        );
+        assert_eq!(
+            LogicVal::resolved(&[LogicVal::One, LogicVal::U]),
+            LogicVal::U
+        );
+        assert_eq!(LogicVec::resolved(&[vec("ZZ1L"), vec("0HZH")]), vec("0H1W"));
+    }
+
+    #[test]
+    fn logic_val_strength_stripping_and_matching() {
+        assert_eq!(vec("UX01ZWLH-").to_x01(), vec("XX01XX01X"));
+        assert_eq!(vec("UX01ZWLH-").to_x01z(), vec("XX01ZX01X"));
+        assert_eq!(vec("UX01ZWLH-").to_ux01(), vec("UX01XX01X"));
+        assert!(vec("01LZ").is_x());
+        assert!(!vec("01LH").is_x());
+        assert!(vec("1-0L").std_match(&vec("H10-")));
+        assert!(!vec("1X").std_match(&vec("1X")));
+        assert!(!vec("10").std_match(&vec("10-")));
+    }
+
+    #[test]
+    fn logic_val_edges_use_x01_values() {
+        assert!(LogicVal::One.is_rising_edge_from(LogicVal::Zero));
+        assert!(LogicVal::H.is_rising_edge_from(LogicVal::L));
+        assert!(!LogicVal::One.is_rising_edge_from(LogicVal::U));
+        assert!(!LogicVal::One.is_rising_edge_from(LogicVal::Z));
+        assert!(LogicVal::L.is_falling_edge_from(LogicVal::One));
+        assert!(!LogicVal::Zero.is_falling_edge_from(LogicVal::X));
+    }
+
+    #[test]
+    fn logic_vec_operators_work_element_wise() {
+        assert_eq!(&vec("01XH") & &vec("1111"), vec("01X1"));
+        assert_eq!(vec("0L0U") | vec("0010"), vec("001U"));
+        assert_eq!(vec("0110") ^ vec("1100"), vec("1010"));
+        assert_eq!(!vec("01UZ"), vec("10UX"));
+        assert_eq!(vec("10H1").nand(&vec("1111")), vec("0100"));
+    }
+
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn logic_vec_operators_reject_length_mismatch() {
+        let _ = vec("01") & vec("011");
+    }
+
+    #[test]
+    fn logic_vec_shifts_fill_with_zero() {
+        assert_eq!(vec("1X01") << 1, vec("X010"));
+        assert_eq!(vec("1X01") >> 2, vec("001X"));
+        assert_eq!(vec("1X01") << 9, vec("0000"));
+        assert_eq!(vec("1X01") >> 4, vec("0000"));
+    }
+
+    #[test]
+    fn logic_vec_slices_and_concatenates() {
+        let v = vec("10XZ01");
+        assert_eq!(v.slice(1..4), vec("0XZ"));
+        assert_eq!(v.slice(..2).concat(&v.slice(4..)), vec("1001"));
+        assert_eq!(v[2], LogicVal::X);
+        assert_eq!(v.get(6), None);
+    }
 }
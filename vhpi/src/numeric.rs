@@ -0,0 +1,683 @@
+//! `numeric_std` arithmetic on logic vectors via [`Unsigned`] and [`Signed`].
+//!
+//! The operators follow `ieee.numeric_std` bit for bit, including result
+//! widths: `+` and `-` return the width of the wider operand, `*` the sum of
+//! both widths, `/` the width of the left operand and `%` and
+//! [`modulo`](Unsigned::modulo) the width of the right operand.  `L` and `H`
+//! count as `0` and `1`.  Where `numeric_std` warns about a metavalue in an
+//! operand the result is all `X`, and comparisons involving metavalues are
+//! unordered, like `NaN`.  Division by zero, which VHDL reports as an error,
+//! also gives all `X`.
+//!
+//! Arithmetic works on vectors of any width.  With the `bigint` feature,
+//! values too wide for `u64` or `i64` can be converted to and from `BigUint`
+//! and `BigInt`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use vhpi::{LogicVec, Unsigned};
+//!
+//! let a = Unsigned::from_uint(200u8, 8);
+//! let b = Unsigned::from_uint(100u8, 8);
+//! assert_eq!((&a + &b).to_integer(), Some(44));
+//! assert_eq!((&a * &b).len(), 16);
+//!
+//! let x = Unsigned::new(LogicVec::from("10X1"));
+//! assert_eq!((&x + &b).to_string(), "xxxxxxxx");
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::{LogicVal, LogicVec};
+
+/// Bits of a vector without metavalues, least significant first.
+type Bits = Vec<bool>;
+
+fn to_bits(vec: &LogicVec) -> Option<Bits> {
+    vec.as_slice()
+        .iter()
+        .rev()
+        .map(|val| match val.to_x01() {
+            LogicVal::Zero => Some(false),
+            LogicVal::One => Some(true),
+            _ => None,
+        })
+        .collect()
+}
+
+fn from_bits(bits: &[bool]) -> LogicVec {
+    bits.iter()
+        .rev()
+        .map(|&bit| if bit { LogicVal::One } else { LogicVal::Zero })
+        .collect()
+}
+
+fn all_x(width: usize) -> LogicVec {
+    LogicVec::new(vec![LogicVal::X; width])
+}
+
+fn is_negative(bits: &[bool]) -> bool {
+    bits.last().copied().unwrap_or(false)
+}
+
+/// Zero or sign extend `bits` to `width`, or truncate them to the `width`
+/// least significant bits.
+fn extend(bits: &[bool], width: usize, signed: bool) -> Bits {
+    let fill = signed && is_negative(bits);
+    (0..width)
+        .map(|i| bits.get(i).copied().unwrap_or(fill))
+        .collect()
+}
+
+/// Sum of two vectors of the same width, wrapping on overflow.
+fn add(a: &[bool], b: &[bool], carry_in: bool) -> Bits {
+    let mut carry = carry_in;
+    a.iter()
+        .zip(b)
+        .map(|(&x, &y)| {
+            let sum = x ^ y ^ carry;
+            carry = (x && y) || (carry && (x ^ y));
+            sum
+        })
+        .collect()
+}
+
+fn invert(bits: &[bool]) -> Bits {
+    bits.iter().map(|&bit| !bit).collect()
+}
+
+fn sub(a: &[bool], b: &[bool]) -> Bits {
+    add(a, &invert(b), true)
+}
+
+fn negate(bits: &[bool]) -> Bits {
+    add(&invert(bits), &vec![false; bits.len()], true)
+}
+
+fn abs(bits: &[bool]) -> Bits {
+    if is_negative(bits) {
+        negate(bits)
+    } else {
+        bits.to_vec()
+    }
+}
+
+/// Product of two vectors of the same width, wrapping on overflow.
+fn mul(a: &[bool], b: &[bool]) -> Bits {
+    let mut product = vec![false; a.len()];
+    for (shift, _) in b.iter().enumerate().filter(|(_, &bit)| bit) {
+        let shifted: Bits = (0..a.len()).map(|i| i >= shift && a[i - shift]).collect();
+        product = add(&product, &shifted, false);
+    }
+    product
+}
+
+/// Compare two unsigned vectors of the same width.
+fn cmp_unsigned(a: &[bool], b: &[bool]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+/// Unsigned long division.  The quotient has the width of `num` and the
+/// remainder the width of `den`.  Returns `None` if `den` is zero.
+fn divmod(num: &[bool], den: &[bool]) -> Option<(Bits, Bits)> {
+    if !den.iter().any(|&bit| bit) {
+        return None;
+    }
+
+    let width = den.len() + 1;
+    let den = extend(den, width, false);
+    let mut quotient = vec![false; num.len()];
+    let mut remainder = vec![false; width];
+    for i in (0..num.len()).rev() {
+        remainder.pop();
+        remainder.insert(0, num[i]);
+        if cmp_unsigned(&remainder, &den) != Ordering::Less {
+            remainder = sub(&remainder, &den);
+            quotient[i] = true;
+        }
+    }
+    remainder.truncate(width - 1);
+    Some((quotient, remainder))
+}
+
+/// Signed division truncating towards zero, returning the quotient and the
+/// remainder, which has the sign of `num`.  Returns `None` if `den` is zero.
+fn divmod_signed(num: &[bool], den: &[bool]) -> Option<(Bits, Bits)> {
+    let (quotient, remainder) = divmod(
+        &abs(&extend(num, num.len() + 1, true)),
+        &abs(&extend(den, den.len() + 1, true)),
+    )?;
+    let quotient = if is_negative(num) != is_negative(den) {
+        negate(&quotient)
+    } else {
+        quotient
+    };
+    let remainder = if is_negative(num) {
+        negate(&remainder)
+    } else {
+        remainder
+    };
+    Some((
+        extend(&quotient, num.len(), true),
+        extend(&remainder, den.len(), true),
+    ))
+}
+
+macro_rules! numeric_type {
+    ($name:ident, $signed:literal, $int:ty) => {
+        impl $name {
+            /// Interprets `bits` as a number, most significant bit first.
+            #[must_use]
+            pub fn new(bits: LogicVec) -> Self {
+                Self(bits)
+            }
+
+            /// Returns the number of bits.
+            #[must_use]
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            /// Returns `true` for a null vector.
+            #[must_use]
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+
+            /// Returns the underlying logic vector.
+            #[must_use]
+            pub fn as_logic_vec(&self) -> &LogicVec {
+                &self.0
+            }
+
+            /// Unwraps the underlying logic vector.
+            #[must_use]
+            pub fn into_logic_vec(self) -> LogicVec {
+                self.0
+            }
+
+            /// Returns `true` if any bit is neither a strong nor a weak `0`
+            /// or `1`.
+            #[must_use]
+            pub fn has_metavalue(&self) -> bool {
+                self.0.is_x()
+            }
+
+            /// Rotates the bits `amount` positions towards the most
+            /// significant bit, as `rotate_left`.
+            #[must_use]
+            pub fn rotate_left(&self, amount: usize) -> Self {
+                if self.is_empty() {
+                    return self.clone();
+                }
+                let amount = amount % self.len();
+                Self(self.0.slice(amount..).concat(&self.0.slice(..amount)))
+            }
+
+            /// Rotates the bits `amount` positions towards the least
+            /// significant bit, as `rotate_right`.
+            #[must_use]
+            pub fn rotate_right(&self, amount: usize) -> Self {
+                if self.is_empty() {
+                    return self.clone();
+                }
+                self.rotate_left(self.len() - amount % self.len())
+            }
+
+            /// Shifts the bits `amount` positions towards the most
+            /// significant bit, filling with `0`, as `shift_left`.
+            #[must_use]
+            pub fn shift_left(&self, amount: usize) -> Self {
+                Self(&self.0 << amount)
+            }
+
+            /// Converts to an integer, as `to_integer`.
+            ///
+            /// Returns `None` if the vector contains a metavalue or the value
+            #[doc = concat!("does not fit in `", stringify!($int), "`.  A null vector converts to 0.")]
+            #[must_use]
+            pub fn to_integer(&self) -> Option<$int> {
+                let bits = to_bits(&self.0)?;
+                let fill = $signed && is_negative(&bits);
+                let width = <$int>::BITS as usize;
+                if bits.iter().skip(width - usize::from($signed)).any(|&bit| bit != fill) {
+                    return None;
+                }
+                Some(
+                    extend(&bits, width, $signed)
+                        .iter()
+                        .rev()
+                        .fold(0, |acc: $int, &bit| (acc << 1) | <$int>::from(bit)),
+                )
+            }
+
+            /// Remainder with the sign of the right operand, as `mod`.
+            ///
+            /// The result has the width of `rhs`, and is all `X` if `rhs` is
+            /// zero.
+            #[must_use]
+            pub fn modulo(&self, rhs: &Self) -> Self {
+                self.checked_binary(rhs, rhs.len(), |a, b| {
+                    let (_, remainder) = Self::divmod(a, b)?;
+                    if $signed
+                        && remainder.iter().any(|&bit| bit)
+                        && is_negative(&remainder) != is_negative(b)
+                    {
+                        Some(add(&remainder, b, false))
+                    } else {
+                        Some(remainder)
+                    }
+                })
+            }
+
+            /// Applies `op` to the operands extended to `width`, producing
+            /// all `X` for metavalues and a null vector for null operands.
+            fn binary(&self, rhs: &Self, width: usize, op: impl Fn(&[bool], &[bool]) -> Bits) -> Self {
+                self.checked_binary(rhs, width, |a, b| Some(op(a, b)))
+            }
+
+            /// Like [`binary`](Self::binary), but also producing all `X`
+            /// where `op` returns `None`.
+            fn checked_binary(
+                &self,
+                rhs: &Self,
+                width: usize,
+                op: impl Fn(&[bool], &[bool]) -> Option<Bits>,
+            ) -> Self {
+                if self.is_empty() || rhs.is_empty() {
+                    return Self(LogicVec::new([]));
+                }
+                match (to_bits(&self.0), to_bits(&rhs.0)) {
+                    (Some(a), Some(b)) => op(&a, &b).map_or_else(|| Self(all_x(width)), |bits| Self(from_bits(&bits))),
+                    _ => Self(all_x(width)),
+                }
+            }
+
+            /// Extends both operands to `width` before applying `op`.
+            fn widened(&self, rhs: &Self, width: usize, op: impl Fn(&[bool], &[bool]) -> Bits) -> Self {
+                self.binary(rhs, width, |a, b| {
+                    op(&extend(a, width, $signed), &extend(b, width, $signed))
+                })
+            }
+
+            fn compare(&self, rhs: &Self) -> Option<Ordering> {
+                let (a, b) = (to_bits(&self.0)?, to_bits(&rhs.0)?);
+                let width = a.len().max(b.len());
+                let (a, b) = (extend(&a, width, $signed), extend(&b, width, $signed));
+                let sign = if $signed {
+                    is_negative(&b).cmp(&is_negative(&a))
+                } else {
+                    Ordering::Equal
+                };
+                Some(sign.then_with(|| cmp_unsigned(&a, &b)))
+            }
+        }
+
+        impl From<LogicVec> for $name {
+            fn from(bits: LogicVec) -> Self {
+                Self(bits)
+            }
+        }
+
+        impl From<$name> for LogicVec {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        /// Numeric equality, as `=`.  Operands of different widths are
+        /// extended first and values with metavalues are never equal.
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.compare(other) == Some(Ordering::Equal)
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.compare(other)
+            }
+        }
+
+        numeric_binop!($name, Add, add, |a: &$name, b: &$name| {
+            a.widened(b, a.len().max(b.len()), |x, y| add(x, y, false))
+        });
+        numeric_binop!($name, Sub, sub, |a: &$name, b: &$name| {
+            a.widened(b, a.len().max(b.len()), sub)
+        });
+        numeric_binop!($name, Mul, mul, |a: &$name, b: &$name| {
+            a.widened(b, a.len() + b.len(), mul)
+        });
+        numeric_binop!($name, Div, div, |a: &$name, b: &$name| {
+            a.checked_binary(b, a.len(), |x, y| Some($name::divmod(x, y)?.0))
+        });
+        numeric_binop!($name, Rem, rem, |a: &$name, b: &$name| {
+            a.checked_binary(b, b.len(), |x, y| Some($name::divmod(x, y)?.1))
+        });
+    };
+}
+
+macro_rules! numeric_binop {
+    ($name:ident, $trait:ident, $method:ident, $body:expr) => {
+        impl std::ops::$trait for &$name {
+            type Output = $name;
+
+            fn $method(self, rhs: &$name) -> $name {
+                ($body)(self, rhs)
+            }
+        }
+
+        impl std::ops::$trait for $name {
+            type Output = $name;
+
+            fn $method(self, rhs: $name) -> $name {
+                std::ops::$trait::$method(&self, &rhs)
+            }
+        }
+    };
+}
+
+/// A `numeric_std.unsigned` value.
+///
+/// Division and remainder by zero give all `X`, where VHDL reports an
+/// error.
+#[derive(Debug, Clone)]
+pub struct Unsigned(LogicVec);
+
+numeric_type!(Unsigned, false, u64);
+
+impl Unsigned {
+    /// Builds a `width` bit value, as `to_unsigned`.  Higher bits of
+    /// `value` are discarded.
+    #[must_use]
+    pub fn from_uint(value: impl Into<u64>, width: usize) -> Self {
+        Self::from(extend_uint(value.into(), width))
+    }
+
+    /// Zero extends or truncates to `width` bits, as `resize`.  A null
+    /// vector resizes to `width` zeros.
+    #[must_use]
+    pub fn resize(&self, width: usize) -> Self {
+        let len = self.len();
+        if width > len {
+            Self(LogicVec::new(vec![LogicVal::Zero; width - len]).concat(&self.0))
+        } else {
+            Self(self.0.slice(len - width..))
+        }
+    }
+
+    /// Shifts the bits `amount` positions towards the least significant
+    /// bit, filling with `0`, as `shift_right`.
+    #[must_use]
+    pub fn shift_right(&self, amount: usize) -> Self {
+        Self(&self.0 >> amount)
+    }
+
+    #[cfg(feature = "bigint")]
+    #[must_use]
+    /// Converts to a `BigUint`, or `None` if the vector contains a
+    /// metavalue.
+    pub fn to_biguint(&self) -> Option<num_bigint::BigUint> {
+        to_bits(&self.0)?;
+        self.0.to_x01().as_biguint()
+    }
+
+    #[cfg(feature = "bigint")]
+    #[must_use]
+    /// Builds a `width` bit value from a `BigUint`.
+    pub fn from_biguint(value: &num_bigint::BigUint, width: usize) -> Self {
+        Self(LogicVec::from_biguint(value, width))
+    }
+
+    fn divmod(num: &[bool], den: &[bool]) -> Option<(Bits, Bits)> {
+        divmod(num, den)
+    }
+}
+
+/// A `numeric_std.signed` value in two's complement.
+///
+/// Division and remainder by zero give all `X`, where VHDL reports an
+/// error.
+#[derive(Debug, Clone)]
+pub struct Signed(LogicVec);
+
+numeric_type!(Signed, true, i64);
+
+impl Signed {
+    /// Builds a `width` bit value, as `to_signed`.  Higher bits of `value`
+    /// are discarded.
+    #[must_use]
+    pub fn from_int(value: impl Into<i64>, width: usize) -> Self {
+        let value: i64 = value.into();
+        let bits = extend_uint(value as u64, width.min(64));
+        Self::from(bits).resize(width)
+    }
+
+    /// Sign extends to `width` bits, or truncates keeping the sign bit and
+    /// the `width - 1` least significant bits, as `resize`.  A null vector
+    /// resizes to `width` zeros.
+    #[must_use]
+    pub fn resize(&self, width: usize) -> Self {
+        if width == 0 {
+            return Self(LogicVec::new([]));
+        }
+        if self.is_empty() {
+            return Self(LogicVec::new(vec![LogicVal::Zero; width]));
+        }
+        let len = self.len();
+        let sign = self.0.slice(..1);
+        if width > len {
+            Self(LogicVec::new(vec![sign[0]; width - len]).concat(&self.0))
+        } else {
+            Self(sign.concat(&self.0.slice(len - width + 1..)))
+        }
+    }
+
+    /// Shifts the bits `amount` positions towards the least significant
+    /// bit, replicating the sign bit, as `shift_right`.
+    #[must_use]
+    pub fn shift_right(&self, amount: usize) -> Self {
+        if self.is_empty() {
+            return self.clone();
+        }
+        let amount = amount.min(self.len());
+        let sign = LogicVec::new(vec![self.0[0]; amount]);
+        Self(sign.concat(&self.0.slice(..self.len() - amount)))
+    }
+
+    /// Absolute value, as `abs`.
+    #[must_use]
+    pub fn abs(&self) -> Self {
+        self.unary(abs)
+    }
+
+    #[cfg(feature = "bigint")]
+    #[must_use]
+    /// Converts to a `BigInt`, or `None` if the vector contains a metavalue.
+    pub fn to_bigint(&self) -> Option<num_bigint::BigInt> {
+        to_bits(&self.0)?;
+        self.0.to_x01().as_bigint()
+    }
+
+    #[cfg(feature = "bigint")]
+    #[must_use]
+    /// Builds a `width` bit value from a `BigInt`.
+    pub fn from_bigint(value: &num_bigint::BigInt, width: usize) -> Self {
+        Self(LogicVec::from_bigint(value, width))
+    }
+
+    fn unary(&self, op: impl Fn(&[bool]) -> Bits) -> Self {
+        match to_bits(&self.0) {
+            Some(bits) => Self(from_bits(&op(&bits))),
+            None => Self(all_x(self.len())),
+        }
+    }
+
+    fn divmod(num: &[bool], den: &[bool]) -> Option<(Bits, Bits)> {
+        divmod_signed(num, den)
+    }
+}
+
+impl std::ops::Neg for &Signed {
+    type Output = Signed;
+
+    fn neg(self) -> Signed {
+        self.unary(negate)
+    }
+}
+
+impl std::ops::Neg for Signed {
+    type Output = Signed;
+
+    fn neg(self) -> Signed {
+        -&self
+    }
+}
+
+fn extend_uint(value: u64, width: usize) -> LogicVec {
+    let bits: Bits = (0..width)
+        .map(|i| i < 64 && (value >> i) & 1 == 1)
+        .collect();
+    from_bits(&bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u(s: &str) -> Unsigned {
+        Unsigned::new(LogicVec::from(s))
+    }
+
+    fn s(s: &str) -> Signed {
+        Signed::new(LogicVec::from(s))
+    }
+
+    #[test]
+    fn unsigned_arithmetic_uses_numeric_std_widths() {
+        let a = Unsigned::from_uint(200u8, 8);
+        let b = Unsigned::from_uint(100u8, 8);
+        assert_eq!((&a + &b).to_integer(), Some(44));
+        assert_eq!((&b - &a).to_string(), "10011100");
+        assert_eq!((&a * &b).to_integer(), Some(20000));
+        assert_eq!((&a * &b).len(), 16);
+        assert_eq!((&a / &Unsigned::from_uint(7u8, 3)).to_integer(), Some(28));
+        assert_eq!((&a % &Unsigned::from_uint(7u8, 3)).to_string(), "100");
+        assert_eq!(a.modulo(&Unsigned::from_uint(7u8, 4)).to_string(), "0100");
+        assert_eq!((u("1") + u("0111")).to_string(), "1000");
+        assert_eq!((u("LH") + u("01")).to_string(), "10");
+    }
+
+    #[test]
+    fn signed_division_follows_vhdl_signs() {
+        let cases = [
+            (7, 2, 3, 1, 1),
+            (-7, 2, -3, -1, 1),
+            (7, -2, -3, 1, -1),
+            (-7, -2, 3, -1, -1),
+        ];
+        for (l, r, quot, rem, modulo) in cases {
+            let (l, r) = (Signed::from_int(l, 5), Signed::from_int(r, 4));
+            assert_eq!((&l / &r).to_integer(), Some(quot));
+            assert_eq!((&l / &r).len(), 5);
+            assert_eq!((&l % &r).to_integer(), Some(rem));
+            assert_eq!((&l % &r).len(), 4);
+            assert_eq!(l.modulo(&r).to_integer(), Some(modulo));
+        }
+        assert_eq!((s("1000") / s("1111")).to_string(), "1000");
+    }
+
+    #[test]
+    fn signed_arithmetic_sign_extends() {
+        assert_eq!((s("1") + s("0011")).to_integer(), Some(2));
+        assert_eq!((s("110") * s("11")).to_integer(), Some(2));
+        assert_eq!((s("110") * s("011")).to_integer(), Some(-6));
+        assert_eq!((-Signed::from_int(5, 4)).to_string(), "1011");
+        assert_eq!(Signed::from_int(-8, 4).abs().to_string(), "1000");
+        assert_eq!(Signed::from_int(-3, 70).to_integer(), Some(-3));
+        assert_eq!(Signed::from_int(-3, 70).len(), 70);
+    }
+
+    #[test]
+    fn metavalues_produce_all_x() {
+        assert_eq!((u("1X") + u("001")).to_string(), "xxx");
+        assert_eq!((s("1Z") * s("01")).to_string(), "xxxx");
+        assert_eq!((u("10") / u("U1")).to_string(), "xx");
+        assert_eq!((-s("0W")).to_string(), "xx");
+        assert_eq!(u("1-").to_integer(), None);
+        assert_eq!((u("") + u("01")).to_string(), "");
+    }
+
+    #[test]
+    fn division_by_zero_produces_all_x() {
+        assert_eq!((u("101") / u("00")).to_string(), "xxx");
+        assert_eq!((u("101") % u("00")).to_string(), "xx");
+        assert_eq!(u("101").modulo(&u("0")).to_string(), "x");
+        assert_eq!((s("0110") / s("000")).to_string(), "xxxx");
+        assert_eq!((s("0110") % s("000")).to_string(), "xxx");
+        assert_eq!(s("1010").modulo(&s("00")).to_string(), "xx");
+    }
+
+    #[test]
+    fn comparison_is_numeric() {
+        assert_eq!(u("0001"), u("1"));
+        assert!(u("10") > u("0001"));
+        assert!(s("10") < s("0001"));
+        assert_eq!(s("1111"), s("1"));
+        assert_ne!(u("X"), u("X"));
+        assert_eq!(u("1X").partial_cmp(&u("00")), None);
+    }
+
+    #[test]
+    fn resize_and_shifts() {
+        assert_eq!(u("101").resize(5).to_string(), "00101");
+        assert_eq!(u("10110").resize(3).to_string(), "110");
+        assert_eq!(s("101").resize(5).to_string(), "11101");
+        assert_eq!(s("10110").resize(3).to_string(), "110");
+        assert_eq!(s("01110").resize(3).to_string(), "010");
+        assert_eq!(u("").resize(3).to_string(), "000");
+        assert_eq!(s("").resize(3).to_string(), "000");
+        assert_eq!(s("10").resize(0).to_string(), "");
+        assert_eq!(u("1011").shift_left(1).to_string(), "0110");
+        assert_eq!(u("1011").shift_right(1).to_string(), "0101");
+        assert_eq!(s("1011").shift_right(2).to_string(), "1110");
+        assert_eq!(u("1011").rotate_left(1).to_string(), "0111");
+        assert_eq!(u("1011").rotate_right(5).to_string(), "1101");
+    }
+
+    #[test]
+    fn to_integer_checks_range() {
+        assert_eq!(u(&format!("{}1", "0".repeat(80))).to_integer(), Some(1));
+        assert_eq!(u(&format!("1{}", "0".repeat(64))).to_integer(), None);
+        assert_eq!(
+            Unsigned::from_uint(u64::MAX, 64).to_integer(),
+            Some(u64::MAX)
+        );
+        assert_eq!(Signed::from_int(i64::MIN, 64).to_integer(), Some(i64::MIN));
+        assert_eq!(s(&format!("01{}", "0".repeat(63))).to_integer(), None);
+        assert_eq!(u("").to_integer(), Some(0));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn wide_values_round_trip_through_bigint() {
+        use num_bigint::{BigInt, BigUint};
+
+        let a = Unsigned::from_biguint(&(BigUint::from(1u8) << 100usize), 101);
+        let b = Unsigned::from_uint(3u8, 2);
+        assert_eq!((&a * &b).to_biguint(), Some(BigUint::from(3u8) << 100usize));
+        assert_eq!(a.to_integer(), None);
+
+        let c = Signed::from_bigint(&-(BigInt::from(1u8) << 90usize), 96);
+        assert_eq!((-&c).to_bigint(), Some(BigInt::from(1u8) << 90usize));
+        assert_eq!(s("1X").to_bigint(), None);
+    }
+}
@@ -0,0 +1,401 @@
+//! Compact storage for wide logic vectors via [`PackedLogicVec`].
+//!
+//! [`LogicVec`] keeps one enum per element and is read through a buffer of
+//! one `vhpiEnumT` per element.  [`PackedLogicVec`] stores each element in
+//! four bits, sixteen to a `u64`, so a multi-kilobit bus takes a few hundred
+//! bytes and can be compared, hashed and counted a word at a time.  All
+//! nine `std_ulogic` values are kept, so conversions in either direction are
+//! lossless.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{Handle, LogicVal, PutValueMode};
+//!
+//! fn check_bus(bus: &Handle, expected: &Handle) -> Result<(), vhpi::Error> {
+//!     let actual = bus.get_packed_logic_vec()?;
+//!     if let Some(index) = actual.first_mismatch(&expected.get_packed_logic_vec()?) {
+//!         vhpi::printf!("mismatch at element {index}");
+//!     }
+//!     vhpi::printf!("{} bits set", actual.count(LogicVal::One));
+//!     bus.put_packed_logic_vec(&actual, PutValueMode::Deposit)
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::{Error, Format, Handle, LogicVal, LogicVec, PutValueMode};
+
+const PER_WORD: usize = 16;
+const NIBBLE_ONES: u64 = 0x1111_1111_1111_1111;
+
+/// Elements in `std_ulogic` order, indexed by their four bit code.
+const DECODE: [LogicVal; 9] = [
+    LogicVal::U,
+    LogicVal::X,
+    LogicVal::Zero,
+    LogicVal::One,
+    LogicVal::Z,
+    LogicVal::W,
+    LogicVal::L,
+    LogicVal::H,
+    LogicVal::DontCare,
+];
+
+fn encode(val: LogicVal) -> u64 {
+    val as u64
+}
+
+fn decode(code: u64) -> LogicVal {
+    DECODE[code as usize]
+}
+
+fn from_enum(raw: vhpi_sys::vhpiEnumT) -> LogicVal {
+    u8::try_from(raw).map_or(LogicVal::X, LogicVal::from)
+}
+
+/// A logic vector stored as four bits per element, most significant element
+/// first like [`LogicVec`].
+///
+/// Unused elements of the last word are always zero, so equality and
+/// hashing work on whole words.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PackedLogicVec {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl PackedLogicVec {
+    /// Creates a vector of `len` copies of `val`.
+    #[must_use]
+    pub fn filled(val: LogicVal, len: usize) -> Self {
+        let mut words = vec![NIBBLE_ONES * encode(val); len.div_ceil(PER_WORD)];
+        if let Some(last) = words.last_mut() {
+            *last &= Self::valid_mask(len, len.div_ceil(PER_WORD) - 1) * 0xf;
+        }
+        Self { len, words }
+    }
+
+    /// Packs a buffer of raw VHPI enumeration values, as filled in by
+    /// `vhpi_get_value` for the `vhpiLogicVecVal` format.
+    ///
+    /// Codes outside the `std_ulogic` range are stored as `X`.
+    #[must_use]
+    pub(crate) fn from_enums(raw: &[vhpi_sys::vhpiEnumT]) -> Self {
+        let mut vec = Self::default();
+        vec.assign_enums(raw);
+        vec
+    }
+
+    /// Repacks from raw VHPI enumeration values, reusing the existing word
+    /// allocation.
+    pub(crate) fn assign_enums(&mut self, raw: &[vhpi_sys::vhpiEnumT]) {
+        self.words.clear();
+        self.words.extend(raw.chunks(PER_WORD).map(|chunk| {
+            chunk.iter().enumerate().fold(0, |word, (i, &code)| {
+                word | encode(from_enum(code)) << (4 * i)
+            })
+        }));
+        self.len = raw.len();
+    }
+
+    /// Unpacks into a buffer of raw VHPI enumeration values for
+    /// `vhpi_put_value`.
+    #[must_use]
+    pub(crate) fn to_enums(&self) -> Vec<vhpi_sys::vhpiEnumT> {
+        self.iter().map(vhpi_sys::vhpiEnumT::from).collect()
+    }
+
+    /// Returns the number of elements.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector has no elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the element at `index` in stored order.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<LogicVal> {
+        (index < self.len)
+            .then(|| decode((self.words[index / PER_WORD] >> (4 * (index % PER_WORD))) & 0xf))
+    }
+
+    /// Replaces the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, val: LogicVal) {
+        assert!(index < self.len, "index {index} out of range");
+        let shift = 4 * (index % PER_WORD);
+        let word = &mut self.words[index / PER_WORD];
+        *word = (*word & !(0xf << shift)) | (encode(val) << shift);
+    }
+
+    /// Appends an element.
+    pub fn push(&mut self, val: LogicVal) {
+        if self.len % PER_WORD == 0 {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, val);
+    }
+
+    /// Returns an iterator over the elements in stored order.
+    pub fn iter(&self) -> impl Iterator<Item = LogicVal> + '_ {
+        (0..self.len).map(|i| decode((self.words[i / PER_WORD] >> (4 * (i % PER_WORD))) & 0xf))
+    }
+
+    /// Counts the elements equal to `val`.
+    #[must_use]
+    pub fn count(&self, val: LogicVal) -> usize {
+        let pattern = NIBBLE_ONES * encode(val);
+        self.words
+            .iter()
+            .enumerate()
+            .map(|(i, &word)| {
+                let x = word ^ pattern;
+                let differs = (x | x >> 1 | x >> 2 | x >> 3) & Self::valid_mask(self.len, i);
+                Self::valid_in_word(self.len, i) - differs.count_ones() as usize
+            })
+            .sum()
+    }
+
+    /// Counts the elements that are a strong or weak `1`.
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        self.count(LogicVal::One) + self.count(LogicVal::H)
+    }
+
+    /// Returns `true` if any element is neither a strong nor a weak `0` or
+    /// `1`, as `Is_X`.
+    #[must_use]
+    pub fn is_x(&self) -> bool {
+        let known = [LogicVal::Zero, LogicVal::One, LogicVal::L, LogicVal::H]
+            .into_iter()
+            .map(|val| self.count(val))
+            .sum::<usize>();
+        known != self.len
+    }
+
+    /// Returns the index of the first element that differs from `other`,
+    /// or `None` if both vectors are identical.
+    ///
+    /// When one vector is a prefix of the other the length of the shorter
+    /// one is returned.
+    #[must_use]
+    pub fn first_mismatch(&self, other: &PackedLogicVec) -> Option<usize> {
+        let len = self.len.min(other.len);
+        let diff = self
+            .words
+            .iter()
+            .zip(&other.words)
+            .enumerate()
+            .find_map(|(i, (a, b))| {
+                let x = (a ^ b) & (Self::valid_mask(len, i) * 0xf);
+                (x != 0).then(|| i * PER_WORD + x.trailing_zeros() as usize / 4)
+            });
+        diff.or((self.len != other.len).then_some(len))
+    }
+
+    /// Number of elements of a vector of length `len` held in word `i`.
+    fn valid_in_word(len: usize, i: usize) -> usize {
+        len.saturating_sub(i * PER_WORD).min(PER_WORD)
+    }
+
+    /// Low bit of each element of word `i` in use by a vector of length
+    /// `len`.
+    fn valid_mask(len: usize, i: usize) -> u64 {
+        match Self::valid_in_word(len, i) {
+            PER_WORD => NIBBLE_ONES,
+            n => NIBBLE_ONES & ((1 << (4 * n)) - 1),
+        }
+    }
+}
+
+impl From<&LogicVec> for PackedLogicVec {
+    fn from(vec: &LogicVec) -> Self {
+        let mut packed = Self {
+            len: vec.len(),
+            words: vec![0; vec.len().div_ceil(PER_WORD)],
+        };
+        for (i, &val) in vec.iter().enumerate() {
+            packed.words[i / PER_WORD] |= encode(val) << (4 * (i % PER_WORD));
+        }
+        packed
+    }
+}
+
+impl From<&PackedLogicVec> for LogicVec {
+    fn from(packed: &PackedLogicVec) -> Self {
+        packed.iter().collect()
+    }
+}
+
+impl FromIterator<LogicVal> for PackedLogicVec {
+    fn from_iter<T: IntoIterator<Item = LogicVal>>(iter: T) -> Self {
+        let mut packed = Self::default();
+        for val in iter {
+            packed.push(val);
+        }
+        packed
+    }
+}
+
+impl fmt::Display for PackedLogicVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s: String = self.iter().map(|val| char::from(u8::from(val))).collect();
+        write!(f, "{s}")
+    }
+}
+
+impl Handle {
+    /// Read a logic vector directly into packed form.
+    ///
+    /// This skips the intermediate [`LogicVec`] built by
+    /// [`Handle::get_value`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the object is not a logic vector or the
+    /// simulator rejects the read.
+    pub fn get_packed_logic_vec(&self) -> Result<PackedLogicVec, Error> {
+        let mut val = vhpi_sys::vhpiValueT {
+            format: Format::LogicVec.into(),
+            bufSize: 0,
+            numElems: 0,
+            unit: vhpi_sys::vhpiPhysS { high: 0, low: 0 },
+            value: vhpi_sys::vhpiValueS__bindgen_ty_1 { longintg: 0 },
+        };
+
+        let mut rc = unsafe { vhpi_sys::vhpi_get_value(self.as_raw(), &raw mut val) };
+        let mut buffer: Vec<vhpi_sys::vhpiEnumT> = vec![];
+        if rc > 0 {
+            buffer = vec![0; rc as usize];
+            val.bufSize = buffer.len() * size_of::<vhpi_sys::vhpiEnumT>();
+            val.value.enumvs = buffer.as_mut_ptr();
+            rc = unsafe { vhpi_sys::vhpi_get_value(self.as_raw(), &raw mut val) };
+        }
+
+        if rc < 0 {
+            return Err(
+                crate::check_error().unwrap_or_else(|| "Unknown error in vhpi_get_value".into())
+            );
+        }
+
+        buffer.truncate(val.numElems as usize);
+        Ok(PackedLogicVec::from_enums(&buffer))
+    }
+
+    /// Write a packed logic vector to this handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the simulator rejects the value write.
+    pub fn put_packed_logic_vec(
+        &self,
+        value: &PackedLogicVec,
+        mode: PutValueMode,
+    ) -> Result<(), Error> {
+        let mut buffer = value.to_enums();
+        let mut val = vhpi_sys::vhpiValueT {
+            format: Format::LogicVec.into(),
+            bufSize: buffer.len() * size_of::<vhpi_sys::vhpiEnumT>(),
+            numElems: buffer
+                .len()
+                .try_into()
+                .map_err(|_| Error::from("vector is too long for VHPI"))?,
+            unit: vhpi_sys::vhpiPhysS { high: 0, low: 0 },
+            value: vhpi_sys::vhpiValueS__bindgen_ty_1 {
+                enumvs: buffer.as_mut_ptr(),
+            },
+        };
+
+        let rc = unsafe { vhpi_sys::vhpi_put_value(self.as_raw(), &raw mut val, mode.into()) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(crate::check_error().unwrap_or_else(|| "Unknown error in vhpi_put_value".into()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_all_values_across_words() {
+        let vec = LogicVec::from("UX01ZWLH-".repeat(5).as_str());
+        let packed = PackedLogicVec::from(&vec);
+
+        assert_eq!(packed.len(), 45);
+        assert_eq!(packed.get(3), Some(LogicVal::One));
+        assert_eq!(packed.get(44), Some(LogicVal::DontCare));
+        assert_eq!(packed.get(45), None);
+        assert_eq!(LogicVec::from(&packed), vec);
+        assert_eq!(packed.to_string(), vec.to_string());
+        assert_eq!(PackedLogicVec::from_enums(&packed.to_enums()), packed);
+    }
+
+    #[test]
+    fn counts_ignore_unused_elements() {
+        let packed = PackedLogicVec::from(&LogicVec::from("1U0H1"));
+        assert_eq!(packed.count(LogicVal::U), 1);
+        assert_eq!(packed.count(LogicVal::One), 2);
+        assert_eq!(packed.count_ones(), 3);
+        assert!(packed.is_x());
+
+        let wide = PackedLogicVec::filled(LogicVal::U, 33);
+        assert_eq!(wide.count(LogicVal::U), 33);
+        assert_eq!(wide.count(LogicVal::Zero), 0);
+        assert!(!PackedLogicVec::filled(LogicVal::L, 40).is_x());
+    }
+
+    #[test]
+    fn equality_and_mismatch_positions() {
+        let mut a = PackedLogicVec::filled(LogicVal::Zero, 40);
+        let b = a.clone();
+        assert_eq!(a, b);
+        assert_eq!(a.first_mismatch(&b), None);
+
+        a.set(37, LogicVal::One);
+        assert_ne!(a, b);
+        assert_eq!(a.first_mismatch(&b), Some(37));
+
+        let c: PackedLogicVec = b.iter().take(20).collect();
+        assert_eq!(b.first_mismatch(&c), Some(20));
+        assert_eq!(c, PackedLogicVec::filled(LogicVal::Zero, 20));
+    }
+
+    #[test]
+    fn equal_vectors_hash_equally() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |packed: &PackedLogicVec| {
+            let mut hasher = DefaultHasher::new();
+            packed.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut pushed = PackedLogicVec::default();
+        for val in LogicVec::from("10XZ").iter() {
+            pushed.push(*val);
+        }
+        assert_eq!(
+            hash(&pushed),
+            hash(&PackedLogicVec::from(&LogicVec::from("10XZ")))
+        );
+    }
+
+    #[test]
+    fn out_of_range_codes_become_x() {
+        let packed = PackedLogicVec::from_enums(&[3, 42, 2]);
+        assert_eq!(packed.to_string(), "1x0");
+    }
+}
@@ -0,0 +1,1017 @@
+//! Parsing VHDL literal syntax into a [`Value`] via [`Value::parse_vhdl`].
+//!
+//! The accepted forms are those a designer would write in VHDL source:
+//!
+//! | Form | Examples |
+//! |------|----------|
+//! | Character literal | `'1'`, `'Z'` |
+//! | String literal | `"0101"`, `"say ""hi"""` |
+//! | Bit string literal | `X"DEAD_BEEF"`, `O"17"`, `B"1010"`, `12UX"F"`, `8SX"F"`, `D"42"` |
+//! | Integer literal | `42`, `-7`, `1E3`, `16#FF#`, `2#1010#E3` |
+//! | Real literal | `3.14`, `1.0E-9`, `16#F.8#` |
+//! | Physical literal | `10 ns`, `1.5 us`, `ms` |
+//! | Enumeration literal | `true`, `idle` |
+//! | Aggregate | `('0', '1')`, `(others => '0')`, `(0 => '1', 3 downto 1 => 'Z', others => '0')` |
+//!
+//! Which [`Value`] variant is produced depends on a [`TypeHint`] describing
+//! the target, normally built with [`TypeHint::from_handle`].
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{Handle, PutValueMode, TypeHint, Value};
+//!
+//! fn force(signal: &Handle, text: &str) -> Result<(), vhpi::Error> {
+//!     let value = Value::parse_vhdl(text, &TypeHint::from_handle(signal)?)?;
+//!     signal.put_value(value, PutValueMode::Force)
+//! }
+//! ```
+
+use crate::{
+    ClassKind, Error, Format, Handle, LogicVal, OneToMany, OneToOne, PhysProperty, Physical, Time,
+    TimeUnit, Value,
+};
+
+/// Description of the object a literal is parsed for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeHint {
+    /// Format the object is read and written in, as reported by
+    /// [`Handle::get_format`].
+    pub format: Format,
+    /// Enumeration literals of the scalar or element type, in position
+    /// order.  Character literals include their quotes, e.g. `'0'`.
+    pub literals: Vec<String>,
+    /// Units of the scalar or element physical type with their value in
+    /// primary units.  When empty, time formats use the units of `TIME`.
+    pub units: Vec<(String, i64)>,
+    /// Left and right bounds of the index range of an array.
+    pub range: Option<(i32, i32)>,
+}
+
+impl TypeHint {
+    /// A hint with only the format known.
+    #[must_use]
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            literals: Vec::new(),
+            units: Vec::new(),
+            range: None,
+        }
+    }
+
+    /// Describe the object `handle` refers to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the simulator cannot report the object's
+    /// format.
+    pub fn from_handle(handle: &Handle) -> Result<Self, Error> {
+        let (format, _) = handle.get_format()?;
+        let mut hint = Self::new(format);
+        let typ = handle.handle(OneToOne::Type);
+        if typ.is_null() {
+            return Ok(hint);
+        }
+
+        let scalar = if element_format(format).is_some() {
+            let mut range = typ.index_range();
+            hint.range = range
+                .next()
+                .map(|left| (left, range.last().unwrap_or(left)));
+            let elem = typ.handle(OneToOne::ElemType);
+            if elem.is_null() {
+                base_type(typ).handle(OneToOne::ElemType)
+            } else {
+                elem
+            }
+        } else {
+            typ
+        };
+
+        let scalar = base_type(scalar);
+        hint.literals = scalar.enum_literals().unwrap_or_default();
+        if scalar.get_kind() == Some(ClassKind::PhysTypeDecl) {
+            hint.units = scalar
+                .iterator(OneToMany::UnitDecls)
+                .filter_map(|unit| {
+                    let scale = unit.get_phys(PhysProperty::PhysPosition).to_i64();
+                    Some((unit.get_name()?, scale))
+                })
+                .collect();
+        }
+        Ok(hint)
+    }
+
+    fn element(&self, format: Format) -> Self {
+        Self {
+            format,
+            range: None,
+            ..self.clone()
+        }
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.range
+            .and_then(|(left, right)| {
+                usize::try_from(i64::from(left).abs_diff(i64::from(right))).ok()
+            })
+            .map(|n| n + 1)
+    }
+
+    /// Offset of index `index` from the left of the range.
+    fn offset(&self, index: i64) -> Result<usize, Error> {
+        let Some((left, right)) = self.range else {
+            return Err("named association needs a known index range".into());
+        };
+        let (left, right) = (i64::from(left), i64::from(right));
+        let offset = if left <= right {
+            (right >= index).then(|| index - left)
+        } else {
+            (right <= index).then(|| left - index)
+        };
+        offset
+            .and_then(|offset| usize::try_from(offset).ok())
+            .ok_or_else(|| {
+                let dir = if left <= right { "to" } else { "downto" };
+                format!("index {index} is outside {left} {dir} {right}")
+                    .as_str()
+                    .into()
+            })
+    }
+
+    fn unit(&self, name: &str) -> Option<i64> {
+        if self.units.is_empty() && matches!(self.format, Format::Time | Format::TimeVec) {
+            TimeUnit::from_name(name).map(|unit| unit.time().to_i64())
+        } else {
+            self.units
+                .iter()
+                .find_map(|(unit, scale)| unit.eq_ignore_ascii_case(name).then_some(*scale))
+        }
+    }
+}
+
+fn base_type(typ: Handle) -> Handle {
+    let base = typ.handle(OneToOne::BaseType);
+    if base.is_null() {
+        typ
+    } else {
+        base
+    }
+}
+
+fn element_format(format: Format) -> Option<Format> {
+    Some(match format {
+        Format::LogicVec => Format::Logic,
+        Format::EnumVec => Format::Enum,
+        Format::SmallEnumVec => Format::SmallEnum,
+        Format::IntVec => Format::Int,
+        Format::LongIntVec => Format::LongInt,
+        Format::RealVec => Format::Real,
+        Format::TimeVec => Format::Time,
+        Format::PhysicalVec => Format::Physical,
+        Format::SmallPhysicalVec => Format::SmallPhysical,
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Int(i64),
+    Real(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Choice {
+    Others,
+    /// Inclusive range of indices, empty when the first is greater.
+    Range(i64, i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Element {
+    /// Empty for positional association.
+    choices: Vec<Choice>,
+    value: Literal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Char(char),
+    /// A string literal, or a bit string literal after expansion.
+    Str(String),
+    Number(Number),
+    Physical(Number, String),
+    Ident(String),
+    Aggregate(Vec<Element>),
+}
+
+impl Literal {
+    fn describe(&self) -> String {
+        match self {
+            Literal::Char(c) => format!("character literal '{c}'"),
+            Literal::Str(s) => format!("string literal \"{s}\""),
+            Literal::Number(Number::Int(n)) => format!("integer literal {n}"),
+            Literal::Number(Number::Real(r)) => format!("real literal {r}"),
+            Literal::Physical(_, unit) => format!("physical literal in {unit}"),
+            Literal::Ident(name) => format!("identifier {name}"),
+            Literal::Aggregate(_) => "aggregate".to_string(),
+        }
+    }
+
+    fn negate(self) -> Option<Self> {
+        let negate = |number| match number {
+            Number::Int(n) => n.checked_neg().map(Number::Int),
+            Number::Real(r) => Some(Number::Real(-r)),
+        };
+        match self {
+            Literal::Number(number) => negate(number).map(Literal::Number),
+            Literal::Physical(number, unit) => negate(number).map(|n| Literal::Physical(n, unit)),
+            _ => None,
+        }
+    }
+}
+
+fn mismatch(expected: &str, literal: &Literal) -> Error {
+    format!("expected {expected}, found {}", literal.describe())
+        .as_str()
+        .into()
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.chars.get(self.pos + n).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += usize::from(c.is_some());
+        c
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        format!("{msg} at column {}", self.pos + 1).as_str().into()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{c}'")))
+        }
+    }
+
+    fn is_ident_char(c: Option<char>) -> bool {
+        c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    /// Whether the keyword `kw` comes next, without consuming it.
+    fn at_keyword(&mut self, kw: &str) -> bool {
+        self.skip_ws();
+        let len = kw.chars().count();
+        self.chars
+            .get(self.pos..self.pos + len)
+            .is_some_and(|word| word.iter().map(char::to_ascii_lowercase).eq(kw.chars()))
+            && !Self::is_ident_char(self.peek_at(len))
+    }
+
+    fn eat_keyword(&mut self, kw: &str) -> bool {
+        let found = self.at_keyword(kw);
+        if found {
+            self.pos += kw.chars().count();
+        }
+        found
+    }
+
+    fn literal(&mut self) -> Result<Literal, Error> {
+        self.skip_ws();
+        match self.peek() {
+            Some('(') => {
+                self.bump();
+                self.aggregate()
+            }
+            Some('\'') => self.character(),
+            Some('"') => self.string().map(Literal::Str),
+            Some(sign @ ('-' | '+')) => {
+                self.bump();
+                self.skip_ws();
+                if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    return Err(self.error("expected a number"));
+                }
+                let literal = self.number()?;
+                if sign == '+' {
+                    Ok(literal)
+                } else {
+                    literal
+                        .negate()
+                        .ok_or_else(|| self.error("integer literal out of range"))
+                }
+            }
+            Some(c) if c.is_alphanumeric() && self.at_bit_string() => self.bit_string(),
+            Some(c) if c.is_ascii_digit() => self.number(),
+            Some(c) if c.is_alphabetic() => Ok(Literal::Ident(self.identifier())),
+            _ => Err(self.error("expected a literal")),
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let mut ident = String::new();
+        while Self::is_ident_char(self.peek()) {
+            ident.extend(self.bump());
+        }
+        ident
+    }
+
+    fn character(&mut self) -> Result<Literal, Error> {
+        self.bump();
+        match (self.bump(), self.bump()) {
+            (Some(c), Some('\'')) => Ok(Literal::Char(c)),
+            _ => Err(self.error("unterminated character literal")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some('"') if self.peek() == Some('"') => {
+                    self.bump();
+                    s.push('"');
+                }
+                Some('"') => return Ok(s),
+                Some(c) => s.push(c),
+                None => return Err(self.error("unterminated string literal")),
+            }
+        }
+    }
+
+    /// Whether a bit string literal such as `X"FF"` or `12UB"1"` comes next.
+    fn at_bit_string(&self) -> bool {
+        let mut n = 0;
+        while self.peek_at(n).is_some_and(|c| c.is_ascii_digit()) {
+            n += 1;
+        }
+        if matches!(self.peek_at(n), Some('u' | 'U' | 's' | 'S')) {
+            n += 1;
+        }
+        matches!(
+            self.peek_at(n),
+            Some('b' | 'B' | 'o' | 'O' | 'x' | 'X' | 'd' | 'D')
+        ) && self.peek_at(n + 1) == Some('"')
+    }
+
+    fn bit_string(&mut self) -> Result<Literal, Error> {
+        let mut width = String::new();
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            width.extend(self.bump());
+        }
+        let signed = match self.peek().map(|c| c.to_ascii_uppercase()) {
+            Some('U') => Some(false),
+            Some('S') => Some(true),
+            _ => None,
+        };
+        if signed.is_some() {
+            self.bump();
+        }
+        let base = self.bump().map(|c| c.to_ascii_uppercase());
+        let digits = self.string()?;
+        let bits = expand_bit_string(base, &digits).map_err(|msg| self.error(&msg))?;
+        if width.is_empty() {
+            return Ok(Literal::Str(bits));
+        }
+        let width = width
+            .parse()
+            .map_err(|_| self.error("bit string length out of range"))?;
+        resize_bit_string(bits, width, signed == Some(true))
+            .map(Literal::Str)
+            .ok_or_else(|| self.error("bit string literal does not fit its length"))
+    }
+
+    fn digits(&mut self, radix: u32) -> Result<String, Error> {
+        let mut digits = String::new();
+        while let Some(c) = self.peek().filter(|&c| c.is_digit(radix) || c == '_') {
+            self.bump();
+            if c != '_' {
+                digits.push(c);
+            }
+        }
+        if digits.is_empty() {
+            Err(self.error("expected digits"))
+        } else {
+            Ok(digits)
+        }
+    }
+
+    fn exponent(&mut self) -> Result<i32, Error> {
+        let signed_digit = matches!(self.peek_at(1), Some('+' | '-'))
+            && self.peek_at(2).is_some_and(|c| c.is_ascii_digit());
+        let digit = self.peek_at(1).is_some_and(|c| c.is_ascii_digit());
+        if !matches!(self.peek(), Some('e' | 'E')) || !(digit || signed_digit) {
+            return Ok(0);
+        }
+        self.bump();
+        let negative = match self.peek() {
+            Some('-') => {
+                self.bump();
+                true
+            }
+            Some('+') => {
+                self.bump();
+                false
+            }
+            _ => false,
+        };
+        let exp: i32 = self
+            .digits(10)?
+            .parse()
+            .map_err(|_| self.error("exponent out of range"))?;
+        Ok(if negative { -exp } else { exp })
+    }
+
+    fn number(&mut self) -> Result<Literal, Error> {
+        let int = self.digits(10)?;
+        let number = if self.peek() == Some('#') {
+            self.bump();
+            let base: u32 = int.parse().unwrap_or(0);
+            if !(2..=16).contains(&base) {
+                return Err(self.error("base must be between 2 and 16"));
+            }
+            let int = self.digits(base)?;
+            let frac = if self.peek() == Some('.') {
+                self.bump();
+                Some(self.digits(base)?)
+            } else {
+                None
+            };
+            if self.bump() != Some('#') {
+                return Err(self.error("expected '#'"));
+            }
+            let exp = self.exponent()?;
+            based_number(base, &int, frac.as_deref(), exp)
+        } else if self.peek() == Some('.') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+            let frac = self.digits(10)?;
+            let exp = self.exponent()?;
+            format!("{int}.{frac}e{exp}").parse().ok().map(Number::Real)
+        } else {
+            let exp = self.exponent()?;
+            based_number(10, &int, None, exp)
+        }
+        .ok_or_else(|| self.error("number out of range"))?;
+
+        self.skip_ws();
+        if self.peek().is_some_and(char::is_alphabetic)
+            && !self.at_keyword("to")
+            && !self.at_keyword("downto")
+        {
+            Ok(Literal::Physical(number, self.identifier()))
+        } else {
+            Ok(Literal::Number(number))
+        }
+    }
+
+    fn choice(&mut self) -> Result<(Literal, Option<Literal>), Error> {
+        let left = self.literal()?;
+        if self.eat_keyword("to") {
+            Ok((left, Some(self.literal()?)))
+        } else if self.eat_keyword("downto") {
+            let right = self.literal()?;
+            Ok((right, Some(left)))
+        } else {
+            Ok((left, None))
+        }
+    }
+
+    fn aggregate(&mut self) -> Result<Literal, Error> {
+        let mut elements = Vec::new();
+        loop {
+            let mut items = vec![self.choice()?];
+            while self.eat('|') {
+                items.push(self.choice()?);
+            }
+            self.skip_ws();
+            let element = if self.peek() == Some('=') && self.peek_at(1) == Some('>') {
+                self.pos += 2;
+                let choices = items
+                    .into_iter()
+                    .map(|item| self.to_choice(item))
+                    .collect::<Result<_, _>>()?;
+                Element {
+                    choices,
+                    value: self.literal()?,
+                }
+            } else if let [(value, None)] = items.as_slice() {
+                Element {
+                    choices: Vec::new(),
+                    value: value.clone(),
+                }
+            } else {
+                return Err(self.error("expected '=>'"));
+            };
+            elements.push(element);
+            if !self.eat(',') {
+                self.expect(')')?;
+                return Ok(Literal::Aggregate(elements));
+            }
+        }
+    }
+
+    fn to_choice(&self, item: (Literal, Option<Literal>)) -> Result<Choice, Error> {
+        match item {
+            (Literal::Ident(name), None) if name.eq_ignore_ascii_case("others") => {
+                Ok(Choice::Others)
+            }
+            (Literal::Number(Number::Int(index)), None) => Ok(Choice::Range(index, index)),
+            (Literal::Number(Number::Int(low)), Some(Literal::Number(Number::Int(high)))) => {
+                Ok(Choice::Range(low, high))
+            }
+            (literal, _) => Err(self.error(&format!("unsupported choice {}", literal.describe()))),
+        }
+    }
+}
+
+fn based_number(base: u32, int: &str, frac: Option<&str>, exp: i32) -> Option<Number> {
+    match frac {
+        Some(frac) => {
+            let mantissa = u64::from_str_radix(&format!("{int}{frac}"), base).ok()?;
+            let scale = i32::try_from(frac.len()).ok()?;
+            let value = mantissa as f64 * f64::from(base).powi(exp - scale);
+            Some(Number::Real(value))
+        }
+        None => {
+            let value = i64::from_str_radix(int, base).ok()?;
+            let scale = i64::from(base).checked_pow(u32::try_from(exp).ok()?)?;
+            value.checked_mul(scale).map(Number::Int)
+        }
+    }
+}
+
+/// Expand the digits of a bit string literal to one character per bit.
+fn expand_bit_string(base: Option<char>, digits: &str) -> Result<String, String> {
+    let digits = digits.chars().filter(|&c| c != '_');
+    let bits_per_digit: u32 = match base {
+        Some('B') => 1,
+        Some('O') => 3,
+        Some('X') => 4,
+        Some('D') => {
+            let digits: String = digits.collect();
+            let value: u128 = digits
+                .parse()
+                .map_err(|_| format!("invalid decimal bit string \"{digits}\""))?;
+            return Ok(format!("{value:b}"));
+        }
+        _ => return Err("invalid bit string base".to_string()),
+    };
+    let mut bits = String::new();
+    for c in digits {
+        if let Some(digit) = c.to_digit(1 << bits_per_digit) {
+            bits.extend((0..bits_per_digit).rev().map(|i| {
+                if (digit >> i) & 1 == 1 {
+                    '1'
+                } else {
+                    '0'
+                }
+            }));
+        } else if "UXZWLH-".contains(c.to_ascii_uppercase()) {
+            bits.extend(std::iter::repeat_n(c, bits_per_digit as usize));
+        } else {
+            return Err(format!("invalid digit '{c}' in bit string"));
+        }
+    }
+    Ok(bits)
+}
+
+/// Extend or truncate expanded bits to `width` as VHDL-2008 does: extension
+/// is with zeros, or copies of the sign bit when `signed`, and only
+/// redundant bits may be truncated.
+fn resize_bit_string(bits: String, width: usize, signed: bool) -> Option<String> {
+    let fill = if signed {
+        bits.chars().next().unwrap_or('0')
+    } else {
+        '0'
+    };
+    let len = bits.chars().count();
+    if width >= len {
+        return Some(
+            std::iter::repeat_n(fill, width - len)
+                .chain(bits.chars())
+                .collect(),
+        );
+    }
+    let (dropped, kept) = bits.split_at(len - width);
+    let fill = if signed {
+        kept.chars().next().unwrap_or('0')
+    } else {
+        '0'
+    };
+    dropped.chars().all(|c| c == fill).then(|| kept.to_string())
+}
+
+fn logic_val(literal: &Literal) -> Result<LogicVal, Error> {
+    match literal {
+        Literal::Char(c) if "UX01ZWLH-".contains(c.to_ascii_uppercase()) => Ok(LogicVal::from(*c)),
+        _ => Err(mismatch("a std_ulogic character literal", literal)),
+    }
+}
+
+fn integer(literal: &Literal) -> Result<i64, Error> {
+    match literal {
+        Literal::Number(Number::Int(n)) => Ok(*n),
+        _ => Err(mismatch("an integer literal", literal)),
+    }
+}
+
+fn real(literal: &Literal) -> Result<f64, Error> {
+    match literal {
+        Literal::Number(Number::Real(r)) => Ok(*r),
+        Literal::Number(Number::Int(n)) => Ok(*n as f64),
+        _ => Err(mismatch("a real literal", literal)),
+    }
+}
+
+fn physical(literal: &Literal, hint: &TypeHint) -> Result<i64, Error> {
+    let (number, unit) = match literal {
+        Literal::Physical(number, unit) => (*number, unit),
+        Literal::Ident(unit) => (Number::Int(1), unit),
+        _ => return Err(mismatch("a physical literal", literal)),
+    };
+    let scale = hint
+        .unit(unit)
+        .ok_or_else(|| Error::from(format!("unknown unit {unit}").as_str()))?;
+    match number {
+        Number::Int(n) => n.checked_mul(scale),
+        Number::Real(r) => {
+            let value = (r * scale as f64).round();
+            (value.abs() < i64::MAX as f64).then_some(value as i64)
+        }
+    }
+    .ok_or_else(|| "physical literal out of range".into())
+}
+
+fn enum_position(literal: &Literal, hint: &TypeHint) -> Result<usize, Error> {
+    if hint.literals.is_empty() {
+        return Err("enumeration literals of the target type are not known".into());
+    }
+    let position = match literal {
+        Literal::Char(c) => hint.literals.iter().position(|name| {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next(), chars.next(), chars.next()) {
+                (Some('\''), Some(n), Some('\''), None) | (Some(n), None, None, None) => n == *c,
+                _ => false,
+            }
+        }),
+        Literal::Ident(ident) => hint
+            .literals
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(ident)),
+        _ => return Err(mismatch("an enumeration literal", literal)),
+    };
+    position.ok_or_else(|| {
+        format!("{} is not a literal of the target type", literal.describe())
+            .as_str()
+            .into()
+    })
+}
+
+fn out_of_range(literal: &Literal) -> Error {
+    format!("{} is out of range", literal.describe())
+        .as_str()
+        .into()
+}
+
+/// The elements of an array literal in left to right order.
+fn elements(literal: Literal, hint: &TypeHint) -> Result<Vec<Literal>, Error> {
+    let values = match literal {
+        Literal::Str(s) => s.chars().map(Literal::Char).collect(),
+        Literal::Aggregate(elements) => resolve_aggregate(elements, hint)?,
+        literal => return Err(mismatch("a string literal or aggregate", &literal)),
+    };
+    match hint.len() {
+        Some(len) if len != values.len() => Err(format!(
+            "{} elements do not fit an array of length {len}",
+            values.len()
+        )
+        .as_str()
+        .into()),
+        _ => Ok(values),
+    }
+}
+
+fn resolve_aggregate(elements: Vec<Element>, hint: &TypeHint) -> Result<Vec<Literal>, Error> {
+    let positional = elements.iter().take_while(|e| e.choices.is_empty()).count();
+    if elements[positional..].iter().any(|e| e.choices.is_empty()) {
+        return Err("positional association follows named association".into());
+    }
+    let len = match hint.len() {
+        Some(len) => len,
+        None if positional == elements.len() => positional,
+        None => return Err("named association needs a known index range".into()),
+    };
+    if positional > len {
+        return Err(format!("aggregate has more than {len} elements")
+            .as_str()
+            .into());
+    }
+
+    let mut slots = vec![None; len];
+    let mut others = None;
+    for (offset, element) in elements.into_iter().enumerate() {
+        if element.choices.is_empty() {
+            slots[offset] = Some(element.value);
+            continue;
+        }
+        for choice in element.choices {
+            match choice {
+                Choice::Others => others = Some(element.value.clone()),
+                Choice::Range(low, high) => {
+                    for index in low..=high {
+                        slots[hint.offset(index)?] = Some(element.value.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    slots
+        .into_iter()
+        .enumerate()
+        .map(|(offset, slot)| {
+            slot.or_else(|| others.clone()).ok_or_else(|| {
+                format!("aggregate has no value for element {offset}")
+                    .as_str()
+                    .into()
+            })
+        })
+        .collect()
+}
+
+/// Collect element values of one variant into a vector.
+macro_rules! collect_elements {
+    ($values:expr, $variant:ident) => {
+        $values
+            .into_iter()
+            .filter_map(|value| match value {
+                Value::$variant(x) => Some(x),
+                _ => None,
+            })
+            .collect()
+    };
+}
+
+fn convert(literal: Literal, hint: &TypeHint) -> Result<Value, Error> {
+    if let Some(format) = element_format(hint.format) {
+        let element_hint = hint.element(format);
+        let values = elements(literal, hint)?
+            .into_iter()
+            .map(|element| convert(element, &element_hint))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(match hint.format {
+            Format::LogicVec => Value::LogicVec(collect_elements!(values, Logic)),
+            Format::EnumVec => Value::EnumVec(collect_elements!(values, Enum)),
+            Format::SmallEnumVec => Value::SmallEnumVec(collect_elements!(values, SmallEnum)),
+            Format::IntVec => Value::IntVec(collect_elements!(values, Int)),
+            Format::LongIntVec => Value::LongIntVec(collect_elements!(values, LongInt)),
+            Format::RealVec => Value::RealVec(collect_elements!(values, Real)),
+            Format::TimeVec => Value::TimeVec(collect_elements!(values, Time)),
+            Format::PhysicalVec => Value::PhysicalVec(collect_elements!(values, Physical)),
+            _ => Value::SmallPhysicalVec(collect_elements!(values, SmallPhysical)),
+        });
+    }
+
+    match hint.format {
+        Format::Logic => logic_val(&literal).map(Value::Logic),
+        Format::Char => match literal {
+            Literal::Char(c) => Ok(Value::Char(c)),
+            literal => Err(mismatch("a character literal", &literal)),
+        },
+        Format::Str => match literal {
+            Literal::Str(s) => Ok(Value::Str(s)),
+            literal => Err(mismatch("a string literal", &literal)),
+        },
+        Format::Int => i32::try_from(integer(&literal)?)
+            .map(Value::Int)
+            .map_err(|_| out_of_range(&literal)),
+        Format::LongInt => integer(&literal).map(Value::LongInt),
+        Format::Real => real(&literal).map(Value::Real),
+        Format::Time => physical(&literal, hint).map(|fs| Value::Time(Time::from(fs))),
+        Format::Physical => physical(&literal, hint).map(|n| Value::Physical(Physical::from(n))),
+        Format::SmallPhysical => i32::try_from(physical(&literal, hint)?)
+            .map(Value::SmallPhysical)
+            .map_err(|_| out_of_range(&literal)),
+        Format::Enum => u32::try_from(enum_position(&literal, hint)?)
+            .map(Value::Enum)
+            .map_err(|_| out_of_range(&literal)),
+        Format::SmallEnum => u8::try_from(enum_position(&literal, hint)?)
+            .map(Value::SmallEnum)
+            .map_err(|_| out_of_range(&literal)),
+        format => Err(format!("cannot parse a literal for format {format:?}")
+            .as_str()
+            .into()),
+    }
+}
+
+impl Value {
+    /// Parse `text` written in VHDL literal syntax into a value of the
+    /// object described by `hint`.
+    ///
+    /// Integer literals are accepted for real objects, a bare unit name
+    /// such as `ns` stands for one of that unit, and a leading sign is
+    /// allowed on numbers.  Aggregates may use positional association,
+    /// `others`, and integer or range choices; choices need
+    /// [`TypeHint::range`] to be known.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` is not a literal, or does not denote a
+    /// value of the object's type.
+    pub fn parse_vhdl(text: &str, hint: &TypeHint) -> Result<Value, Error> {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            pos: 0,
+        };
+        let literal = parser.literal()?;
+        parser.skip_ws();
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected text after literal"));
+        }
+        convert(literal, hint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogicVec;
+
+    fn parse(text: &str, hint: &TypeHint) -> Value {
+        Value::parse_vhdl(text, hint).unwrap()
+    }
+
+    fn logic_vec(range: (i32, i32)) -> TypeHint {
+        TypeHint {
+            range: Some(range),
+            ..TypeHint::new(Format::LogicVec)
+        }
+    }
+
+    #[test]
+    fn parses_logic_literals() {
+        let logic = TypeHint::new(Format::Logic);
+        assert_eq!(parse("'1'", &logic), Value::Logic(LogicVal::One));
+        assert_eq!(parse(" 'z' ", &logic), Value::Logic(LogicVal::Z));
+        assert!(Value::parse_vhdl("'q'", &logic).is_err());
+        assert!(Value::parse_vhdl("1", &logic).is_err());
+
+        let byte = logic_vec((7, 0));
+        let expect = |s: &str| Value::LogicVec(LogicVec::from(s));
+        assert_eq!(parse("\"0101ZZXX\"", &byte), expect("0101ZZXX"));
+        assert_eq!(parse("X\"A5\"", &byte), expect("10100101"));
+        assert_eq!(parse("b\"1010_0101\"", &byte), expect("10100101"));
+        assert_eq!(parse("O\"245\"", &logic_vec((8, 0))), expect("010100101"));
+        assert_eq!(parse("8X\"F\"", &byte), expect("00001111"));
+        assert_eq!(parse("8SX\"F\"", &byte), expect("11111111"));
+        assert_eq!(parse("8D\"165\"", &byte), expect("10100101"));
+        assert_eq!(parse("X\"Z-\"", &byte), expect("ZZZZ----"));
+        assert!(Value::parse_vhdl("X\"A5\"", &logic_vec((3, 0))).is_err());
+        assert!(Value::parse_vhdl("4X\"F0\"", &logic_vec((3, 0))).is_err());
+        assert!(Value::parse_vhdl("X\"G\"", &byte).is_err());
+    }
+
+    #[test]
+    fn parses_aggregates() {
+        let expect = |s: &str| Value::LogicVec(LogicVec::from(s));
+        assert_eq!(
+            parse("(others => '0')", &logic_vec((7, 0))),
+            expect("00000000")
+        );
+        assert_eq!(
+            parse(
+                "(0 => '1', 3 downto 2 | 7 => 'Z', others => '0')",
+                &logic_vec((7, 0))
+            ),
+            expect("Z000ZZ01")
+        );
+        assert_eq!(
+            parse("(0 to 1 => 'H', OTHERS => 'L')", &logic_vec((0, 3))),
+            expect("HHLL")
+        );
+        assert_eq!(
+            parse("('1', '0', others => 'U')", &logic_vec((3, 0))),
+            expect("10UU")
+        );
+        assert_eq!(
+            parse("('1', '0')", &TypeHint::new(Format::LogicVec)),
+            expect("10")
+        );
+        assert!(Value::parse_vhdl("(others => '0')", &TypeHint::new(Format::LogicVec)).is_err());
+        assert!(Value::parse_vhdl("(8 => '1', others => '0')", &logic_vec((7, 0))).is_err());
+        assert!(Value::parse_vhdl("(0 => '1')", &logic_vec((1, 0))).is_err());
+        assert!(Value::parse_vhdl("(0 => '1', '0')", &logic_vec((1, 0))).is_err());
+
+        let ints = TypeHint::new(Format::IntVec);
+        assert_eq!(
+            parse("(1, -2, 16#10#)", &ints),
+            Value::IntVec(vec![1, -2, 16])
+        );
+    }
+
+    #[test]
+    fn parses_numbers() {
+        let int = TypeHint::new(Format::Int);
+        assert_eq!(parse("42", &int), Value::Int(42));
+        assert_eq!(parse("-1_000", &int), Value::Int(-1000));
+        assert_eq!(parse("1E3", &int), Value::Int(1000));
+        assert_eq!(parse("16#FF#", &int), Value::Int(255));
+        assert_eq!(parse("2#1010#E3", &int), Value::Int(80));
+        assert!(Value::parse_vhdl("3000000000", &int).is_err());
+        assert!(Value::parse_vhdl("1E-3", &int).is_err());
+        assert!(Value::parse_vhdl("17#1#", &int).is_err());
+        assert!(Value::parse_vhdl("42 43", &int).is_err());
+        assert_eq!(
+            parse("3000000000", &TypeHint::new(Format::LongInt)),
+            Value::LongInt(3_000_000_000)
+        );
+
+        let real = TypeHint::new(Format::Real);
+        assert_eq!(parse("2.75", &real), Value::Real(2.75));
+        assert_eq!(parse("-1.5E-3", &real), Value::Real(-1.5e-3));
+        assert_eq!(parse("16#F.8#", &real), Value::Real(15.5));
+        assert_eq!(parse("2", &real), Value::Real(2.0));
+    }
+
+    #[test]
+    fn parses_physical_literals() {
+        let time = TypeHint::new(Format::Time);
+        assert_eq!(
+            parse("10 ns", &time),
+            Value::Time(Time::from(10_000_000_i64))
+        );
+        assert_eq!(
+            parse("1.5 US", &time),
+            Value::Time(Time::from(1_500_000_000_i64))
+        );
+        assert_eq!(parse("ps", &time), Value::Time(Time::from(1_000_i64)));
+        assert!(Value::parse_vhdl("10", &time).is_err());
+        assert!(Value::parse_vhdl("10 parsecs", &time).is_err());
+
+        let resistance = TypeHint {
+            units: vec![("ohm".to_string(), 1), ("kohm".to_string(), 1000)],
+            ..TypeHint::new(Format::Physical)
+        };
+        assert_eq!(
+            parse("4.7 kohm", &resistance),
+            Value::Physical(Physical::from(4700_i64))
+        );
+    }
+
+    #[test]
+    fn parses_enumeration_literals() {
+        let boolean = TypeHint {
+            literals: vec!["FALSE".to_string(), "TRUE".to_string()],
+            ..TypeHint::new(Format::SmallEnum)
+        };
+        assert_eq!(parse("true", &boolean), Value::SmallEnum(1));
+        assert!(Value::parse_vhdl("maybe", &boolean).is_err());
+
+        let bits = TypeHint {
+            literals: vec!["'0'".to_string(), "'1'".to_string()],
+            range: Some((0, 3)),
+            ..TypeHint::new(Format::EnumVec)
+        };
+        assert_eq!(parse("\"0110\"", &bits), Value::EnumVec(vec![0, 1, 1, 0]));
+        assert_eq!(parse("X\"9\"", &bits), Value::EnumVec(vec![1, 0, 0, 1]));
+
+        assert_eq!(parse("'a'", &TypeHint::new(Format::Char)), Value::Char('a'));
+        assert_eq!(
+            parse("\"say \"\"hi\"\"\"", &TypeHint::new(Format::Str)),
+            Value::Str("say \"hi\"".to_string())
+        );
+        assert!(Value::parse_vhdl("\"open", &TypeHint::new(Format::Str)).is_err());
+    }
+}
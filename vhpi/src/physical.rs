@@ -1,3 +1,32 @@
+//! Physical values, and [`PhysicalValue`] which carries the units of its
+//! type.
+//!
+//! [`PhysicalType::from_handle`] resolves the unit declarations of any
+//! physical type, such as a user-defined frequency or resistance, so that a
+//! [`PhysicalValue`] can be displayed in its best unit, parsed from a
+//! literal such as `1.5 kohm` and converted between units.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::Handle;
+//!
+//! fn report(signal: &Handle) -> Result<(), vhpi::Error> {
+//!     let freq = signal.get_physical()?;
+//!     vhpi::printf!("{} = {freq} ({:.3})", signal.get_name().unwrap_or_default(), freq);
+//!     if let Some(mhz) = freq.as_unit("MHz") {
+//!         vhpi::printf!("{mhz} MHz");
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::{
+    ClassKind, Error, Format, Handle, OneToMany, OneToOne, PhysProperty, TimeUnit, TypeHint, Value,
+};
+
 #[derive(Debug, Clone, PartialEq)]
 /// Physical quantity represented as a split 64-bit value.
 pub struct Physical {
@@ -51,6 +80,325 @@ impl Physical {
     }
 }
 
+/// Format `value / scale` with `precision` decimal places, rounding half
+/// away from zero.
+pub(crate) fn format_scaled(value: i64, scale: i64, precision: usize) -> String {
+    // 10**18 is the largest power of ten that cannot overflow below;
+    // further places are always zero.
+    let places = precision.min(18);
+    let pow = 10_i128.pow(places as u32);
+    let value = i128::from(value) * pow;
+    let scale = i128::from(scale);
+    let rounded = (2 * value + value.signum() * scale) / (2 * scale);
+    let sign = if rounded < 0 { "-" } else { "" };
+    let (int, frac) = (rounded.abs() / pow, rounded.abs() % pow);
+    if precision == 0 {
+        format!("{sign}{int}")
+    } else {
+        let zeros = "0".repeat(precision - places);
+        format!("{sign}{int}.{frac:0places$}{zeros}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A unit of a physical type.
+pub struct PhysicalUnit {
+    /// Name of the unit as declared.
+    pub name: String,
+    /// Number of base units in one of this unit.
+    pub scale: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A physical type and its units.
+pub struct PhysicalType {
+    name: Option<String>,
+    units: Vec<PhysicalUnit>,
+}
+
+impl PhysicalType {
+    /// A physical type with the given units.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no unit has a scale of 1, or a scale is not
+    /// positive.
+    pub fn new(name: Option<String>, mut units: Vec<PhysicalUnit>) -> Result<Self, Error> {
+        if let Some(unit) = units.iter().find(|unit| unit.scale <= 0) {
+            return Err(format!("unit {} has scale {}", unit.name, unit.scale)
+                .as_str()
+                .into());
+        }
+        if !units.iter().any(|unit| unit.scale == 1) {
+            return Err("physical type has no base unit".into());
+        }
+        units.sort_by_key(|unit| unit.scale);
+        Ok(Self { name, units })
+    }
+
+    #[must_use]
+    /// The units of `TIME`.
+    pub fn time() -> Self {
+        let units = TimeUnit::ALL
+            .into_iter()
+            .map(|unit| PhysicalUnit {
+                name: unit.vhdl_name().to_string(),
+                scale: unit.time().to_i64(),
+            })
+            .collect();
+        Self {
+            name: Some("TIME".to_string()),
+            units,
+        }
+    }
+
+    /// Resolve the units of a physical type declaration, or of the type of
+    /// an object or the element type of an array object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type is not physical or the value of a unit
+    /// cannot be determined.
+    pub fn from_handle(handle: &Handle) -> Result<Self, Error> {
+        if matches!(
+            handle.get_kind(),
+            Some(ClassKind::PhysTypeDecl | ClassKind::SubtypeDecl)
+        ) {
+            return Self::from_type(handle);
+        }
+        Self::from_type(&element_or_self(handle.handle(OneToOne::Type)))
+    }
+
+    fn from_type(typ: &Handle) -> Result<Self, Error> {
+        let base = typ.handle(OneToOne::BaseType);
+        let decl = if base.is_null() { typ } else { &base };
+        if decl.get_kind() != Some(ClassKind::PhysTypeDecl) {
+            return Err("not a physical type".into());
+        }
+
+        let mut units = Vec::new();
+        if let Some(name) = decl.handle(OneToOne::BaseUnit).get_name() {
+            units.push(PhysicalUnit { name, scale: 1 });
+        }
+        for unit in decl.iterator(OneToMany::UnitDecls) {
+            let Some(name) = unit.get_name() else {
+                continue;
+            };
+            if units.iter().any(|u| u.name.eq_ignore_ascii_case(&name)) {
+                continue;
+            }
+            let scale = unit_scale(&unit)
+                .ok_or_else(|| Error::from(format!("unit {name} has no value").as_str()))?;
+            units.push(PhysicalUnit { name, scale });
+        }
+        Self::new(decl.get_name(), units)
+    }
+
+    #[must_use]
+    /// Name of the type, if known.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    #[must_use]
+    /// Units, smallest first.
+    pub fn units(&self) -> &[PhysicalUnit] {
+        &self.units
+    }
+
+    #[must_use]
+    /// The primary unit, with a scale of 1.
+    pub fn base_unit(&self) -> &PhysicalUnit {
+        &self.units[0]
+    }
+
+    #[must_use]
+    /// Find a unit by name, ignoring case.
+    pub fn unit(&self, name: &str) -> Option<&PhysicalUnit> {
+        self.units
+            .iter()
+            .find(|unit| unit.name.eq_ignore_ascii_case(name))
+    }
+
+    #[must_use]
+    /// A value of this type, in base units.
+    pub fn value(&self, value: i64) -> PhysicalValue {
+        PhysicalValue {
+            value,
+            typ: self.clone(),
+        }
+    }
+
+    /// Parse a physical literal such as `1.5 kHz` or `-3 mV` using the
+    /// units of this type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` is not a physical literal, names an
+    /// unknown unit, or is out of range.
+    pub fn parse(&self, text: &str) -> Result<PhysicalValue, Error> {
+        let hint = TypeHint {
+            units: self
+                .units
+                .iter()
+                .map(|unit| (unit.name.clone(), unit.scale))
+                .collect(),
+            ..TypeHint::new(Format::Physical)
+        };
+        match Value::parse_vhdl(text, &hint)? {
+            Value::Physical(phys) => Ok(self.value(phys.to_i64())),
+            _ => Err("not a physical literal".into()),
+        }
+    }
+}
+
+/// Value of a unit declaration in base units.
+fn unit_scale(unit: &Handle) -> Option<i64> {
+    let position = unit.get_phys(PhysProperty::PhysPosition).to_i64();
+    if position > 0 {
+        return Some(position);
+    }
+    let literal = unit.handle(OneToOne::PhysLiteral);
+    if literal.is_null() {
+        return None;
+    }
+    let value = literal.get_phys(PhysProperty::PhysVal).to_i64();
+    (value > 0).then_some(value)
+}
+
+/// The element type of an array type, otherwise the type itself.
+fn element_or_self(typ: Handle) -> Handle {
+    let base = typ.handle(OneToOne::BaseType);
+    let is_array = if base.is_null() {
+        typ.get_kind()
+    } else {
+        base.get_kind()
+    } == Some(ClassKind::ArrayTypeDecl);
+    if !is_array {
+        return typ;
+    }
+    let elem = typ.handle(OneToOne::ElemType);
+    if elem.is_null() {
+        base.handle(OneToOne::ElemType)
+    } else {
+        elem
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A value of a physical type, with the units needed to display it.
+pub struct PhysicalValue {
+    /// The value in base units.
+    pub value: i64,
+    /// The type the value belongs to.
+    pub typ: PhysicalType,
+}
+
+impl PhysicalValue {
+    #[must_use]
+    /// The largest unit that represents the value exactly.
+    pub fn best_unit(&self) -> &PhysicalUnit {
+        self.typ
+            .units
+            .iter()
+            .rev()
+            .find(|unit| self.value != 0 && self.value % unit.scale == 0)
+            .unwrap_or_else(|| self.typ.base_unit())
+    }
+
+    #[must_use]
+    /// The value as a possibly fractional number of the unit `name`.
+    pub fn as_unit(&self, name: &str) -> Option<f64> {
+        let unit = self.typ.unit(name)?;
+        Some(self.value as f64 / unit.scale as f64)
+    }
+
+    #[must_use]
+    /// The value as a whole number of the unit `name`, or `None` if it is
+    /// not a multiple of that unit.
+    pub fn as_whole_unit(&self, name: &str) -> Option<i64> {
+        let unit = self.typ.unit(name)?;
+        (self.value % unit.scale == 0).then(|| self.value / unit.scale)
+    }
+
+    #[must_use]
+    /// Format as a number of the unit `name` with `precision` decimal
+    /// places, e.g. `3.30 V`, or `None` if the unit is unknown.
+    pub fn format_in(&self, name: &str, precision: usize) -> Option<String> {
+        let unit = self.typ.unit(name)?;
+        let value = format_scaled(self.value, unit.scale, precision);
+        Some(format!("{value} {}", unit.name))
+    }
+
+    #[must_use]
+    /// Split representation of the value.
+    pub fn as_physical(&self) -> Physical {
+        Physical::from(self.value)
+    }
+
+    #[must_use]
+    /// Wraps the value as a [`Value::Physical`].
+    pub fn as_value(&self) -> Value {
+        Value::Physical(self.as_physical())
+    }
+}
+
+impl fmt::Display for PhysicalValue {
+    /// Formats in the largest unit that shows the value exactly, or with a
+    /// precision such as `{:.2}` in the largest unit not exceeding it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match f.precision() {
+            Some(_) => self
+                .typ
+                .units
+                .iter()
+                .rev()
+                .find(|unit| self.value.unsigned_abs() >= unit.scale.unsigned_abs())
+                .unwrap_or_else(|| self.typ.base_unit()),
+            None => self.best_unit(),
+        };
+        let value = format_scaled(self.value, unit.scale, f.precision().unwrap_or(0));
+        write!(f, "{value} {}", unit.name)
+    }
+}
+
+impl Handle {
+    /// Read an object of a physical type, with the units of its type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object is not of a physical type or the
+    /// simulator rejects the read.
+    pub fn get_physical(&self) -> Result<PhysicalValue, Error> {
+        let typ = PhysicalType::from_handle(self)?;
+        let value = match self.get_value(Format::ObjType)? {
+            Value::Physical(phys) => phys.to_i64(),
+            Value::SmallPhysical(value) => i64::from(value),
+            Value::Time(time) => time.to_i64(),
+            _ => return Err("object is not of a physical type".into()),
+        };
+        Ok(typ.value(value))
+    }
+
+    /// Read an array of a physical type, with the units of its element
+    /// type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object is not an array of a physical type or
+    /// the simulator rejects the read.
+    pub fn get_physical_vec(&self) -> Result<Vec<PhysicalValue>, Error> {
+        let typ = PhysicalType::from_handle(self)?;
+        let values: Vec<i64> = match self.get_value(Format::ObjType)? {
+            Value::PhysicalVec(values) => values.iter().map(Physical::to_i64).collect(),
+            Value::SmallPhysicalVec(values) => values.into_iter().map(i64::from).collect(),
+            Value::TimeVec(values) => values.iter().map(crate::Time::to_i64).collect(),
+            _ => return Err("object is not an array of a physical type".into()),
+        };
+        Ok(values.into_iter().map(|value| typ.value(value)).collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,4 +436,66 @@ mod tests {
         assert_eq!(raw_round_trip.low, 0x89AB_CDEF);
         assert_eq!(raw_round_trip.high, 0x0123_4567);
     }
+
+    fn frequency() -> PhysicalType {
+        let unit = |name: &str, scale| PhysicalUnit {
+            name: name.to_string(),
+            scale,
+        };
+        PhysicalType::new(
+            Some("FREQUENCY_T".to_string()),
+            vec![
+                unit("kHz", 1_000),
+                unit("Hz", 1),
+                unit("MHz", 1_000_000),
+                unit("GHz", 1_000_000_000),
+                unit("THz", 1_000_000_000_000),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn physical_value_displays_best_unit() {
+        let freq = frequency();
+        assert_eq!(freq.base_unit().name, "Hz");
+        assert_eq!(freq.value(100_000_000).to_string(), "100 MHz");
+        assert_eq!(freq.value(100_000_000_000_000).to_string(), "100 THz");
+        assert_eq!(freq.value(1_500).to_string(), "1500 Hz");
+        assert_eq!(freq.value(0).to_string(), "0 Hz");
+        assert_eq!(freq.value(-2_000).to_string(), "-2 kHz");
+        assert_eq!(format!("{:.2}", freq.value(1_500)), "1.50 kHz");
+        assert_eq!(format!("{:.1}", freq.value(999)), "999.0 Hz");
+        assert_eq!(PhysicalType::time().value(5_000_000).to_string(), "5 ns");
+    }
+
+    #[test]
+    fn physical_value_parses_and_converts_units() {
+        let freq = frequency();
+        let value = freq.parse("2.5 ghz").unwrap();
+        assert_eq!(value.value, 2_500_000_000);
+        assert_eq!(value.as_unit("MHz"), Some(2_500.0));
+        assert_eq!(value.as_whole_unit("MHz"), Some(2_500));
+        assert_eq!(value.as_whole_unit("GHz"), None);
+        assert_eq!(value.as_unit("parsec"), None);
+        assert_eq!(value.format_in("GHz", 3).as_deref(), Some("2.500 GHz"));
+        assert_eq!(freq.parse("kHz").unwrap().value, 1_000);
+        assert!(freq.parse("10 V").is_err());
+        assert!(freq.parse("10").is_err());
+        assert_eq!(
+            value.as_value(),
+            Value::Physical(Physical::from(2_500_000_000_i64))
+        );
+    }
+
+    #[test]
+    fn physical_type_needs_base_unit() {
+        let unit = |scale| PhysicalUnit {
+            name: "u".to_string(),
+            scale,
+        };
+        assert!(PhysicalType::new(None, vec![unit(1_000)]).is_err());
+        assert!(PhysicalType::new(None, vec![unit(1), unit(0)]).is_err());
+        assert!(PhysicalType::new(None, Vec::new()).is_err());
+    }
 }
@@ -0,0 +1,331 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{
+    get_time, register_cb, remove_cb, CbReason, ClassKind, Error, Handle, OneToMany,
+    RegisterCbError, SourceLocation, StrProperty, Time,
+};
+
+/// Return `true` for class kinds that execute as VHDL processes.
+///
+/// Concurrent signal assignments and concurrent assertions are included since
+/// they are equivalent to a process.
+pub(crate) fn is_process_kind(kind: &ClassKind) -> bool {
+    matches!(
+        kind,
+        ClassKind::ProcessStmt
+            | ClassKind::CondSigAssignStmt
+            | ClassKind::SelectSigAssignStmt
+            | ClassKind::SimpleSigAssignStmt
+            | ClassKind::ConcAssertStmt
+    )
+}
+
+#[derive(Debug)]
+/// Static description of a process in a region.
+pub struct ProcessInfo {
+    /// Handle to the process statement.
+    pub handle: Handle,
+    /// Statement label, or the name assigned by the simulator when the
+    /// process is unlabelled.
+    pub label: Option<String>,
+    /// Class of the statement, e.g. [`ClassKind::ProcessStmt`].
+    pub kind: Option<ClassKind>,
+    /// Location of the statement in the VHDL source.
+    pub location: Option<SourceLocation>,
+    /// Signals in the sensitivity list.
+    pub sensitivity: Vec<Handle>,
+    /// Sequential statements in the process body.  Empty for equivalent
+    /// processes of concurrent statements.
+    pub statements: Vec<Handle>,
+}
+
+impl ProcessInfo {
+    #[must_use]
+    /// Describe the process statement `handle`.
+    pub fn new(handle: Handle) -> Self {
+        let label = handle
+            .get_str(StrProperty::LabelName)
+            .or_else(|| handle.get_name());
+        let kind = handle.get_kind();
+        let location = handle.source_location();
+        let sensitivity = handle.iterator(OneToMany::Sensitivities).collect();
+        let statements = if kind == Some(ClassKind::ProcessStmt) {
+            handle.iterator(OneToMany::SeqStmts).collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            handle,
+            label,
+            kind,
+            location,
+            sensitivity,
+            statements,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Activity of a process reported to [`Handle::register_resume_cb`] and
+/// [`Handle::register_suspend_cb`] callbacks.
+pub struct ProcessEvent<'a> {
+    /// The process that resumed or suspended.
+    pub process: &'a Handle,
+    /// Either [`CbReason::Resume`] or [`CbReason::Suspend`].
+    pub reason: CbReason,
+    /// Simulation time of the event.
+    pub time: Time,
+    /// Why the process resumed.  Always `None` for suspend events.
+    pub trigger: Option<Trigger<'a>>,
+}
+
+#[derive(Debug, PartialEq)]
+/// Why a process resumed, as reported in [`ProcessEvent::trigger`].
+///
+/// VHPI does not report the cause of a resumption, so it is reconstructed
+/// from value-change callbacks on the signals the process is sensitive to:
+/// its sensitivity list and the `on` clauses of the wait statements at the
+/// top level of its body.
+pub enum Trigger<'a> {
+    /// These watched signals had an event in the current cycle.
+    Event(Vec<&'a Handle>),
+    /// No watched signal had an event.  The process resumed during
+    /// initialization, after the timeout of a `wait for`, or on a signal the
+    /// simulator does not report as a sensitivity.
+    Timeout,
+}
+
+/// The callbacks registered by [`Handle::register_resume_cb`] or
+/// [`Handle::register_suspend_cb`].
+///
+/// Resume callbacks need helper callbacks on the process's signals to report
+/// [`ProcessEvent::trigger`].  All of them are removed together when this
+/// value is dropped or [`remove`](Self::remove) is called, so keep it for as
+/// long as the callback should fire.
+#[derive(Debug)]
+#[must_use = "the process callback is removed when this value is dropped"]
+pub struct ProcessCallback {
+    /// The resume or suspend callback first, then its helpers.
+    handles: Vec<Handle>,
+}
+
+impl ProcessCallback {
+    /// Handle of the resume or suspend callback, for example to disable it.
+    #[must_use]
+    pub fn handle(&self) -> &Handle {
+        &self.handles[0]
+    }
+
+    /// Remove the callback and its helpers.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error the simulator reports.  The remaining
+    /// callbacks are still removed.
+    pub fn remove(mut self) -> Result<(), Error> {
+        std::mem::take(&mut self.handles)
+            .iter()
+            .map(remove_cb)
+            .fold(Ok(()), Result::and)
+    }
+}
+
+impl Drop for ProcessCallback {
+    fn drop(&mut self) {
+        for handle in &self.handles {
+            let _ = remove_cb(handle);
+        }
+    }
+}
+
+/// Signals a process is sensitive to and which of them had an event in the
+/// current simulation cycle.
+struct WakeTracker<T> {
+    signals: Vec<T>,
+    changed: RefCell<Vec<bool>>,
+}
+
+impl<T: PartialEq> WakeTracker<T> {
+    fn new(signals: impl IntoIterator<Item = T>) -> Self {
+        let mut unique: Vec<T> = Vec::new();
+        for signal in signals {
+            if !unique.contains(&signal) {
+                unique.push(signal);
+            }
+        }
+        let changed = RefCell::new(vec![false; unique.len()]);
+        Self {
+            signals: unique,
+            changed,
+        }
+    }
+
+    fn mark(&self, index: usize) {
+        self.changed.borrow_mut()[index] = true;
+    }
+
+    fn clear(&self) {
+        self.changed.borrow_mut().fill(false);
+    }
+
+    /// Signals that changed since the last [`WakeTracker::clear`], or `None`
+    /// if there were none.
+    fn changed(&self) -> Option<Vec<&T>> {
+        let changed: Vec<&T> = self
+            .signals
+            .iter()
+            .zip(self.changed.borrow().iter())
+            .filter_map(|(signal, &changed)| changed.then_some(signal))
+            .collect();
+        (!changed.is_empty()).then_some(changed)
+    }
+}
+
+impl Handle {
+    #[must_use]
+    /// Enumerate the processes declared directly in this region.
+    ///
+    /// Covers process statements and the equivalent processes of concurrent
+    /// statements.  Nested regions are not visited.
+    pub fn processes(&self) -> Vec<ProcessInfo> {
+        let stmts = self
+            .iterator(OneToMany::Stmts)
+            .filter(|stmt| stmt.get_kind().is_some_and(|kind| is_process_kind(&kind)));
+        let mut processes: Vec<ProcessInfo> = stmts.map(ProcessInfo::new).collect();
+
+        for stmt in self.iterator(OneToMany::EqProcessStmts) {
+            if !processes.iter().any(|p| p.handle == stmt) {
+                processes.push(ProcessInfo::new(stmt));
+            }
+        }
+
+        processes
+    }
+
+    /// Register a callback invoked each time this process resumes.
+    ///
+    /// The event's [`ProcessEvent::trigger`] names the signals that woke the
+    /// process.  To find them, this also registers a value-change callback on
+    /// each signal the process is sensitive to and a repeating
+    /// `EndOfProcesses` callback.  The returned [`ProcessCallback`] owns all
+    /// of them and removes them when dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RegisterCbError::Error`] when the simulator reports an error
+    /// while registering any of the callbacks.  Those already registered are
+    /// removed again.
+    pub fn register_resume_cb<F>(&self, callback: F) -> Result<ProcessCallback, RegisterCbError>
+    where
+        F: Fn(&ProcessEvent) + 'static,
+    {
+        self.register_process_cb(CbReason::Resume, callback)
+    }
+
+    /// Register a callback invoked each time this process suspends.
+    ///
+    /// The callback is removed when the returned [`ProcessCallback`] is
+    /// dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RegisterCbError::Error`] when the simulator reports an error
+    /// while registering the callback.
+    pub fn register_suspend_cb<F>(&self, callback: F) -> Result<ProcessCallback, RegisterCbError>
+    where
+        F: Fn(&ProcessEvent) + 'static,
+    {
+        self.register_process_cb(CbReason::Suspend, callback)
+    }
+
+    fn register_process_cb<F>(
+        &self,
+        reason: CbReason,
+        callback: F,
+    ) -> Result<ProcessCallback, RegisterCbError>
+    where
+        F: Fn(&ProcessEvent) + 'static,
+    {
+        let mut helpers = ProcessCallback {
+            handles: Vec::new(),
+        };
+        let tracker = if reason == CbReason::Resume {
+            Some(self.watch_sensitivity(&mut helpers.handles)?)
+        } else {
+            None
+        };
+
+        let cb_reason = reason.clone();
+        let handle = self.register_cb(reason, move |data| {
+            let trigger = tracker
+                .as_ref()
+                .map(|tracker| tracker.changed().map_or(Trigger::Timeout, Trigger::Event));
+            callback(&ProcessEvent {
+                process: data.obj(),
+                reason: cb_reason.clone(),
+                time: get_time(),
+                trigger,
+            });
+        })?;
+        helpers.handles.insert(0, handle);
+        Ok(helpers)
+    }
+
+    /// Track events on the signals this process is sensitive to, resetting
+    /// at the end of each process execution phase.  The callbacks doing so
+    /// are added to `handles`.
+    fn watch_sensitivity(
+        &self,
+        handles: &mut Vec<Handle>,
+    ) -> Result<Rc<WakeTracker<Handle>>, RegisterCbError> {
+        let waits = self
+            .iterator(OneToMany::SeqStmts)
+            .filter(|stmt| stmt.get_kind() == Some(ClassKind::WaitStmt))
+            .flat_map(|stmt| stmt.iterator(OneToMany::Sensitivities).collect::<Vec<_>>());
+        let tracker = Rc::new(WakeTracker::new(
+            self.iterator(OneToMany::Sensitivities).chain(waits),
+        ));
+
+        for (index, signal) in tracker.signals.iter().enumerate() {
+            let tracker = Rc::clone(&tracker);
+            handles.push(signal.register_cb(CbReason::ValueChange, move |_| tracker.mark(index))?);
+        }
+
+        let clear = Rc::clone(&tracker);
+        handles.push(register_cb(CbReason::RepEndOfProcesses, move |_| {
+            clear.clear();
+        })?);
+
+        Ok(tracker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_statements_count_as_processes() {
+        assert!(is_process_kind(&ClassKind::ProcessStmt));
+        assert!(is_process_kind(&ClassKind::CondSigAssignStmt));
+        assert!(!is_process_kind(&ClassKind::WaitStmt));
+    }
+
+    #[test]
+    fn tracker_reports_changed_signals_until_cleared() {
+        let tracker = WakeTracker::new(["clk", "rst", "clk"]);
+        assert_eq!(tracker.signals, ["clk", "rst"]);
+        assert_eq!(tracker.changed(), None);
+
+        tracker.mark(1);
+        assert_eq!(tracker.changed(), Some(vec![&"rst"]));
+
+        tracker.mark(0);
+        assert_eq!(tracker.changed(), Some(vec![&"clk", &"rst"]));
+
+        tracker.clear();
+        assert_eq!(tracker.changed(), None);
+    }
+}
@@ -0,0 +1,463 @@
+//! Allocation-free repeated reads via [`ValueReader`].
+//!
+//! [`Handle::get_value`] makes two `vhpi_get_value` calls per read, one to
+//! size the buffer and one to fill it, and copies the result into a fresh
+//! [`Value`].  A [`ValueReader`] remembers the format and element count
+//! from the first query and keeps its buffers between calls, so each
+//! subsequent read is a single `vhpi_get_value` into storage that is
+//! already large enough.  Reading into a caller-owned [`LogicVec`],
+//! [`PackedLogicVec`] or `Vec` therefore allocates nothing once the
+//! buffers have grown to the value's size.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::cell::RefCell;
+//! use vhpi::{CbReason, Handle, LogicVec, ValueReader};
+//!
+//! fn watch(bus: &Handle) -> Result<(), vhpi::Error> {
+//!     let state = RefCell::new((ValueReader::new(bus)?, LogicVec::default()));
+//!     bus.register_cb(CbReason::ValueChange, move |data| {
+//!         let (reader, value) = &mut *state.borrow_mut();
+//!         if reader.read_into(data.obj(), value).is_ok() {
+//!             vhpi::printf!("bus is now {value}");
+//!         }
+//!     })
+//!     .expect("failed to register value change callback");
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{Error, Format, Handle, LogicVec, PackedLogicVec, Value};
+
+/// Reusable reader for objects of a fixed format and size.
+///
+/// The reader only caches the value's shape, not the handle, so it may be
+/// used with any object of the same type, such as the `obj` of a callback.
+/// If a read finds the value has grown, the buffers are resized and the
+/// read is retried once.
+#[derive(Debug, Clone)]
+pub struct ValueReader {
+    format: Format,
+    len: usize,
+    enums: Vec<vhpi_sys::vhpiEnumT>,
+    chars: Vec<vhpi_sys::vhpiCharT>,
+}
+
+impl ValueReader {
+    /// Creates a reader for `handle` using the format and element count
+    /// reported by [`Handle::get_format`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the simulator cannot report the object's
+    /// format.
+    pub fn new(handle: &Handle) -> Result<Self, Error> {
+        let mut reader = Self::sized(Format::ObjType, 0);
+        reader.refresh(handle)?;
+        Ok(reader)
+    }
+
+    /// Creates a reader that requests `format` rather than the object's
+    /// native format.
+    ///
+    /// The element count is not known up front, so the first read performs
+    /// the sizing pass.
+    #[must_use]
+    pub fn with_format(format: Format) -> Self {
+        Self::sized(format, 0)
+    }
+
+    fn sized(format: Format, len: usize) -> Self {
+        Self {
+            format,
+            len,
+            enums: Vec::new(),
+            chars: Vec::new(),
+        }
+    }
+
+    /// Re-queries the format and element count from `handle`, keeping the
+    /// existing buffers.
+    ///
+    /// Use this to move a reader to an object of a different type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the simulator cannot report the object's
+    /// format.
+    pub fn refresh(&mut self, handle: &Handle) -> Result<(), Error> {
+        let (format, len) = handle.get_format()?;
+        self.format = format;
+        self.len = usize::try_from(len).unwrap_or(0);
+        Ok(())
+    }
+
+    /// Returns the format this reader requests.
+    #[must_use]
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the element count the buffers are currently sized for.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the reader is sized for a scalar or empty value.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reads a logic vector into `out`, reusing its allocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the reader's format is not
+    /// [`Format::LogicVec`] or the simulator rejects the read.
+    pub fn read_into(&mut self, handle: &Handle, out: &mut LogicVec) -> Result<(), Error> {
+        self.read_logic_enums(handle)?;
+        out.assign_slice(&self.enums);
+        Ok(())
+    }
+
+    /// Reads a logic vector into packed form, reusing the allocation of
+    /// `out`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the reader's format is not
+    /// [`Format::LogicVec`] or the simulator rejects the read.
+    pub fn read_packed_into(
+        &mut self,
+        handle: &Handle,
+        out: &mut PackedLogicVec,
+    ) -> Result<(), Error> {
+        self.read_logic_enums(handle)?;
+        out.assign_enums(&self.enums);
+        Ok(())
+    }
+
+    /// Reads raw enumeration values into `out`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the reader's format is neither
+    /// [`Format::EnumVec`] nor [`Format::LogicVec`], or the simulator
+    /// rejects the read.
+    pub fn read_enums_into(&mut self, handle: &Handle, out: &mut Vec<u32>) -> Result<(), Error> {
+        self.expect(&[Format::EnumVec, Format::LogicVec], "an enum vector")?;
+        fetch(handle, self.format, &mut self.len, out)
+    }
+
+    /// Reads an integer vector into `out`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the reader's format is not
+    /// [`Format::IntVec`] or the simulator rejects the read.
+    pub fn read_ints_into(&mut self, handle: &Handle, out: &mut Vec<i32>) -> Result<(), Error> {
+        self.expect(&[Format::IntVec], "an integer vector")?;
+        fetch(handle, self.format, &mut self.len, out)
+    }
+
+    /// Reads a real vector into `out`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the reader's format is not
+    /// [`Format::RealVec`] or the simulator rejects the read.
+    pub fn read_reals_into(&mut self, handle: &Handle, out: &mut Vec<f64>) -> Result<(), Error> {
+        self.expect(&[Format::RealVec], "a real vector")?;
+        fetch(handle, self.format, &mut self.len, out)
+    }
+
+    /// Reads a string-formatted value into `out`, reusing its allocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the reader's format is not one of the string
+    /// formats or the simulator rejects the read.
+    pub fn read_str_into(&mut self, handle: &Handle, out: &mut String) -> Result<(), Error> {
+        self.expect(STRING_FORMATS, "a string")?;
+        fetch(handle, self.format, &mut self.len, &mut self.chars)?;
+        out.clear();
+        out.extend(
+            self.chars
+                .iter()
+                .take_while(|&&c| c != 0)
+                .map(|&c| char::from(c)),
+        );
+        Ok(())
+    }
+
+    /// Reads the value into a new [`Value`].
+    ///
+    /// This skips the sizing pass but still allocates the result; prefer
+    /// the `read_*_into` methods in hot paths.  Scalars and vector formats
+    /// without a dedicated method are read with [`Handle::get_value`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the simulator rejects the read.
+    pub fn read(&mut self, handle: &Handle) -> Result<Value, Error> {
+        match self.format {
+            Format::LogicVec => {
+                let mut out = LogicVec::default();
+                self.read_into(handle, &mut out)?;
+                Ok(out.as_value())
+            }
+            Format::EnumVec => {
+                let mut out = Vec::new();
+                self.read_enums_into(handle, &mut out)?;
+                Ok(Value::EnumVec(out))
+            }
+            Format::IntVec => {
+                let mut out = Vec::new();
+                self.read_ints_into(handle, &mut out)?;
+                Ok(Value::IntVec(out))
+            }
+            Format::RealVec => {
+                let mut out = Vec::new();
+                self.read_reals_into(handle, &mut out)?;
+                Ok(Value::RealVec(out))
+            }
+            Format::BinStr | Format::OctStr | Format::HexStr | Format::DecStr | Format::Str => {
+                let mut out = String::new();
+                self.read_str_into(handle, &mut out)?;
+                Ok(match self.format {
+                    Format::BinStr => Value::BinStr(out),
+                    Format::OctStr => Value::OctStr(out),
+                    Format::HexStr => Value::HexStr(out),
+                    Format::DecStr => Value::DecStr(out),
+                    _ => Value::Str(out),
+                })
+            }
+            format => handle.get_value(format),
+        }
+    }
+
+    fn read_logic_enums(&mut self, handle: &Handle) -> Result<(), Error> {
+        self.expect(&[Format::LogicVec], "a logic vector")?;
+        fetch(handle, self.format, &mut self.len, &mut self.enums)
+    }
+
+    fn expect(&self, formats: &[Format], what: &str) -> Result<(), Error> {
+        if formats.contains(&self.format) {
+            Ok(())
+        } else {
+            Err(format!("cannot read {:?} value as {what}", self.format)
+                .as_str()
+                .into())
+        }
+    }
+}
+
+const STRING_FORMATS: &[Format] = &[
+    Format::BinStr,
+    Format::OctStr,
+    Format::HexStr,
+    Format::DecStr,
+    Format::Str,
+];
+
+/// Reads a vector value into `buf` with a single `vhpi_get_value` when
+/// `len` already covers it.
+///
+/// If the simulator reports that the buffer is too small, `len` is updated
+/// and the read retried once.  On success `buf` holds exactly the elements
+/// returned.
+fn fetch<T: Copy + Default>(
+    handle: &Handle,
+    format: Format,
+    len: &mut usize,
+    buf: &mut Vec<T>,
+) -> Result<(), Error> {
+    let truncate = !STRING_FORMATS.contains(&format);
+    fetch_with(len, buf, truncate, |buf| {
+        let mut val = vhpi_sys::vhpiValueT {
+            format: format.into(),
+            bufSize: size_of_val(buf),
+            numElems: 0,
+            unit: vhpi_sys::vhpiPhysS { high: 0, low: 0 },
+            value: vhpi_sys::vhpiValueS__bindgen_ty_1 { longintg: 0 },
+        };
+        bind_buffer(&mut val, buf.as_mut_ptr().cast());
+
+        let rc = unsafe { vhpi_sys::vhpi_get_value(handle.as_raw(), &raw mut val) };
+
+        if rc < 0 {
+            Err(crate::check_error().unwrap_or_else(|| "Unknown error in vhpi_get_value".into()))
+        } else if rc > 0 {
+            Ok(Fill::TooSmall(rc as usize))
+        } else {
+            Ok(Fill::Filled(usize::try_from(val.numElems).unwrap_or(0)))
+        }
+    })
+}
+
+/// Result of one read into a buffer of the current size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fill {
+    /// The value was written and has this many elements.
+    Filled(usize),
+    /// The buffer is too small; the value needs this many elements.
+    TooSmall(usize),
+}
+
+/// The sizing and retry logic of [`fetch`], with the read done by `get`.
+///
+/// `truncate` trims `buf` to the element count `get` reports; string
+/// formats keep the whole buffer since they are NUL-terminated.
+fn fetch_with<T: Copy + Default>(
+    len: &mut usize,
+    buf: &mut Vec<T>,
+    truncate: bool,
+    mut get: impl FnMut(&mut [T]) -> Result<Fill, Error>,
+) -> Result<(), Error> {
+    for _ in 0..2 {
+        buf.clear();
+        buf.resize(*len, T::default());
+
+        match get(buf)? {
+            Fill::TooSmall(needed) => *len = needed,
+            Fill::Filled(count) => {
+                if truncate {
+                    buf.truncate(count);
+                }
+                return Ok(());
+            }
+        }
+    }
+
+    Err("value size changed during read".into())
+}
+
+/// Points the union member matching the value's format at `ptr`.
+pub(crate) fn bind_buffer(val: &mut vhpi_sys::vhpiValueT, ptr: *mut u8) {
+    match val.format {
+        vhpi_sys::vhpiFormatT_vhpiBinStrVal
+        | vhpi_sys::vhpiFormatT_vhpiStrVal
+        | vhpi_sys::vhpiFormatT_vhpiOctStrVal
+        | vhpi_sys::vhpiFormatT_vhpiHexStrVal
+        | vhpi_sys::vhpiFormatT_vhpiDecStrVal => val.value.str_ = ptr,
+        vhpi_sys::vhpiFormatT_vhpiLogicVecVal | vhpi_sys::vhpiFormatT_vhpiEnumVecVal => {
+            val.value.enumvs = ptr.cast();
+        }
+        vhpi_sys::vhpiFormatT_vhpiRealVecVal => val.value.reals = ptr.cast(),
+        vhpi_sys::vhpiFormatT_vhpiIntVecVal => val.value.intgs = ptr.cast(),
+        vhpi_sys::vhpiFormatT_vhpiLongIntVecVal => val.value.longintgs = ptr.cast(),
+        vhpi_sys::vhpiFormatT_vhpiSmallEnumVecVal => val.value.smallenumvs = ptr.cast(),
+        _ => panic!("unsupported vector format {}", val.format),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_mismatched_formats() {
+        let reader = ValueReader::with_format(Format::IntVec);
+        assert_eq!(reader.format(), Format::IntVec);
+        assert!(reader.is_empty());
+        assert!(reader
+            .expect(&[Format::IntVec], "an integer vector")
+            .is_ok());
+        assert_eq!(
+            reader
+                .expect(&[Format::LogicVec], "a logic vector")
+                .unwrap_err()
+                .message,
+            "cannot read IntVec value as a logic vector"
+        );
+    }
+
+    /// A value of `value.len()` elements read through [`fetch_with`],
+    /// counting the reads.
+    fn fill<'a>(
+        value: &'a [u32],
+        reads: &'a mut usize,
+    ) -> impl FnMut(&mut [u32]) -> Result<Fill, Error> + 'a {
+        move |buf| {
+            *reads += 1;
+            if buf.len() < value.len() {
+                return Ok(Fill::TooSmall(value.len()));
+            }
+            buf[..value.len()].copy_from_slice(value);
+            Ok(Fill::Filled(value.len()))
+        }
+    }
+
+    #[test]
+    fn sized_fetch_reads_once() {
+        let (mut len, mut buf, mut reads) = (3, Vec::new(), 0);
+        fetch_with(&mut len, &mut buf, true, fill(&[7, 8, 9], &mut reads)).unwrap();
+        assert_eq!((reads, len), (1, 3));
+        assert_eq!(buf, [7, 8, 9]);
+    }
+
+    #[test]
+    fn fetch_grows_and_retries() {
+        let (mut len, mut buf, mut reads) = (1, vec![5], 0);
+        fetch_with(&mut len, &mut buf, true, fill(&[1, 2, 3, 4], &mut reads)).unwrap();
+        assert_eq!((reads, len), (2, 4));
+        assert_eq!(buf, [1, 2, 3, 4]);
+
+        // The grown size is kept, so the next read is a single pass.
+        reads = 0;
+        fetch_with(&mut len, &mut buf, true, fill(&[4, 3, 2, 1], &mut reads)).unwrap();
+        assert_eq!((reads, buf.as_slice()), (1, [4, 3, 2, 1].as_slice()));
+    }
+
+    #[test]
+    fn fetch_truncates_only_vectors() {
+        let (mut len, mut buf, mut reads) = (5, Vec::new(), 0);
+        fetch_with(&mut len, &mut buf, true, fill(&[1, 2], &mut reads)).unwrap();
+        assert_eq!(buf, [1, 2]);
+
+        fetch_with(&mut len, &mut buf, false, fill(&[1, 2], &mut reads)).unwrap();
+        assert_eq!(buf, [1, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn fetch_gives_up_when_size_keeps_changing() {
+        let mut needed = 0;
+        let mut len = 0;
+        let err = fetch_with(&mut len, &mut Vec::<u32>::new(), true, |_| {
+            needed += 2;
+            Ok(Fill::TooSmall(needed))
+        })
+        .unwrap_err();
+        assert_eq!(err.message, "value size changed during read");
+        assert_eq!(len, 4);
+    }
+
+    #[test]
+    fn fetch_passes_read_errors_through() {
+        let mut len = 2;
+        let err = fetch_with(&mut len, &mut Vec::<u32>::new(), true, |_| {
+            Err("no such object".into())
+        })
+        .unwrap_err();
+        assert_eq!(err.message, "no such object");
+    }
+
+    #[test]
+    fn reassigning_reuses_storage() {
+        let mut logic = LogicVec::default();
+        logic.assign_slice(&[2, 3, 4, 1]);
+        assert_eq!(logic, LogicVec::from("01ZX"));
+        logic.assign_slice(&[3]);
+        assert_eq!(logic, LogicVec::from("1"));
+        // Codes that do not fit a byte are unknown, not wrapped to `1`.
+        logic.assign_slice(&[0x103]);
+        assert_eq!(logic, LogicVec::from("X"));
+
+        let mut packed = PackedLogicVec::from_enums(&[3; 40]);
+        packed.assign_enums(&[2, 3]);
+        assert_eq!(packed, PackedLogicVec::from_enums(&[2, 3]));
+    }
+}
@@ -0,0 +1,249 @@
+//! Inspecting the VHDL call stack via [`StackFrame`].
+//!
+//! While a subprogram is running, either from a
+//! [`CbReason::StartOfSubpCall`](crate::CbReason::StartOfSubpCall) or
+//! [`CbReason::EndOfSubpCall`](crate::CbReason::EndOfSubpCall) callback or
+//! inside a foreign subprogram, [`StackFrame::current`] returns the frame of
+//! the innermost call.  Frames can be walked towards the caller with
+//! [`StackFrame::up`], and [`Backtrace::capture`] collects the whole chain.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use vhpi::{Backtrace, CbReason, Severity};
+//!
+//! extern "C" fn backtrace_startup() {
+//!     vhpi::register_cb(CbReason::EndOfSubpCall, |_| {
+//!         let backtrace = Backtrace::capture();
+//!         if backtrace.frames().len() > 32 {
+//!             vhpi::assert(Severity::Warning, format!("deep recursion\n{backtrace}"));
+//!         }
+//!     })
+//!     .expect("failed to register end of subprogram callback");
+//! }
+//!
+//! vhpi::startup_routines!(backtrace_startup);
+//! ```
+
+use std::fmt;
+
+use crate::{
+    handle, ClassKind, Format, Handle, IntProperty, OneToMany, OneToOne, SourceLocation,
+    StrProperty, Value,
+};
+
+/// A parameter or variable declared in a stack frame.
+#[derive(Debug)]
+pub struct FrameLocal {
+    /// Name of the parameter or variable.
+    pub name: String,
+    /// Class of the declaration, e.g. [`ClassKind::VarDecl`].
+    pub class: Option<ClassKind>,
+    /// Current value, `None` if the simulator cannot read it.
+    pub value: Option<Value>,
+    /// Handle to the declaration.
+    pub handle: Handle,
+}
+
+impl FrameLocal {
+    fn new(handle: Handle) -> Self {
+        Self {
+            name: handle.get_name().unwrap_or_default(),
+            class: handle.get_kind(),
+            value: handle.get_value(Format::ObjType).ok(),
+            handle,
+        }
+    }
+}
+
+/// One active subprogram call on the VHDL call stack.
+#[derive(Debug)]
+pub struct StackFrame {
+    handle: Handle,
+}
+
+fn non_null(handle: Handle) -> Option<Handle> {
+    if handle.is_null() {
+        None
+    } else {
+        Some(handle)
+    }
+}
+
+impl StackFrame {
+    #[must_use]
+    /// Frame of the innermost subprogram call currently running.
+    ///
+    /// Returns `None` when no subprogram is executing.
+    pub fn current() -> Option<Self> {
+        non_null(handle(OneToOne::CurStackFrame)).map(Self::from_handle)
+    }
+
+    #[must_use]
+    /// Wrap a stack frame handle, e.g. one obtained from a callback.
+    pub fn from_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+
+    #[must_use]
+    /// Handle to the underlying stack frame object.
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    #[must_use]
+    /// Take the underlying stack frame handle.
+    pub fn into_handle(self) -> Handle {
+        self.handle
+    }
+
+    #[must_use]
+    /// Frame of the caller, or `None` when this subprogram was called
+    /// directly from a process or concurrent statement.
+    pub fn up(&self) -> Option<Self> {
+        non_null(self.handle.handle(OneToOne::UpStack)).map(Self::from_handle)
+    }
+
+    #[must_use]
+    /// Frame of the subprogram this one is currently calling, if any.
+    pub fn down(&self) -> Option<Self> {
+        non_null(self.handle.handle(OneToOne::DownStack)).map(Self::from_handle)
+    }
+
+    #[must_use]
+    /// Depth of this frame, as reported by the simulator.
+    pub fn level(&self) -> i32 {
+        self.handle.get(IntProperty::FrameLevel)
+    }
+
+    #[must_use]
+    /// Name of the subprogram executing in this frame.
+    pub fn subprogram(&self) -> Option<String> {
+        let body = self.handle.handle(OneToOne::SubpBody);
+        if body.is_null() {
+            self.handle.get_name()
+        } else {
+            body.get_name()
+                .or_else(|| body.get_str(StrProperty::UnitName))
+        }
+    }
+
+    #[must_use]
+    /// Source location of the statement executing in this frame.
+    pub fn location(&self) -> Option<SourceLocation> {
+        self.handle.source_location()
+    }
+
+    #[must_use]
+    /// Line of the statement executing in this frame.
+    pub fn line(&self) -> Option<u32> {
+        self.location().map(|location| location.begin_line)
+    }
+
+    #[must_use]
+    /// Parameters followed by variables declared in this frame, with their
+    /// current values.
+    pub fn locals(&self) -> Vec<FrameLocal> {
+        self.handle
+            .iterator(OneToMany::ParamDecls)
+            .chain(self.handle.iterator(OneToMany::VarDecls))
+            .map(FrameLocal::new)
+            .collect()
+    }
+
+    #[must_use]
+    /// Find a parameter or variable of this frame by name, ignoring case.
+    pub fn local(&self, name: &str) -> Option<FrameLocal> {
+        self.locals()
+            .into_iter()
+            .find(|local| local.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_frame(
+            f,
+            self.level(),
+            self.subprogram().as_deref(),
+            self.location().as_ref(),
+        )
+    }
+}
+
+fn write_frame(
+    f: &mut fmt::Formatter<'_>,
+    level: i32,
+    subprogram: Option<&str>,
+    location: Option<&SourceLocation>,
+) -> fmt::Result {
+    write!(f, "#{level} {}", subprogram.unwrap_or("<unknown>"))?;
+    if let Some(location) = location {
+        write!(f, " at {location}")?;
+    }
+    Ok(())
+}
+
+/// The chain of active subprogram calls, innermost first.
+#[derive(Debug, Default)]
+pub struct Backtrace {
+    frames: Vec<StackFrame>,
+}
+
+impl Backtrace {
+    #[must_use]
+    /// Collect the frames from [`StackFrame::current`] up to the outermost
+    /// subprogram call.
+    ///
+    /// The backtrace is empty when no subprogram is executing.
+    pub fn capture() -> Self {
+        let frames = std::iter::successors(StackFrame::current(), StackFrame::up).collect();
+        Self { frames }
+    }
+
+    #[must_use]
+    /// Frames of the backtrace, innermost first.
+    pub fn frames(&self) -> &[StackFrame] {
+        &self.frames
+    }
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, frame) in self.frames.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{frame}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Frame<'a>(i32, Option<&'a str>, Option<&'a SourceLocation>);
+
+    impl fmt::Display for Frame<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write_frame(f, self.0, self.1, self.2)
+        }
+    }
+
+    #[test]
+    fn formats_frames() {
+        let location = SourceLocation {
+            file: "pkg.vhdl".to_string(),
+            begin_line: 17,
+            end_line: 17,
+            design_unit: Some("PKG".to_string()),
+        };
+        assert_eq!(
+            Frame(2, Some("CHECK_PACKET"), Some(&location)).to_string(),
+            "#2 CHECK_PACKET at pkg.vhdl:17"
+        );
+        assert_eq!(Frame(0, None, None).to_string(), "#0 <unknown>");
+    }
+}
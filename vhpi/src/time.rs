@@ -1,6 +1,9 @@
 use num_traits::Zero;
 
-use crate::{check_error, Error, Physical};
+use std::time::Duration;
+
+use crate::physical::format_scaled;
+use crate::{check_error, simulator_time_resolution, Error, Format, Physical, TypeHint, Value};
 
 /// 1 femtosecond — the base VHDL time unit (`vhpiFS`).
 pub const FS: Time = Time { high: 0, low: 1 };
@@ -48,8 +51,13 @@ const S_I64: i64 = S.to_i64();
 const MN_I64: i64 = MN.to_i64();
 const HR_I64: i64 = HR.to_i64();
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 /// Simulation time represented as a split 64-bit value.
+///
+/// Times are ordered and do arithmetic on the full 64-bit count of
+/// femtoseconds.  As with the primitive integers, the operators panic on
+/// overflow in debug builds; use the `checked_` and `saturating_` methods
+/// where overflow is possible.
 pub struct Time {
     /// Low 32 bits of the time value.
     pub low: u32,
@@ -59,10 +67,7 @@ pub struct Time {
 
 impl From<i64> for Time {
     fn from(value: i64) -> Self {
-        Time {
-            low: value as u32,
-            high: (value >> 32) as i32,
-        }
+        Time::from_i64(value)
     }
 }
 
@@ -111,8 +116,150 @@ impl std::ops::Mul<Time> for Time {
     }
 }
 
+impl PartialOrd for Time {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Time {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.to_i64().cmp(&other.to_i64())
+    }
+}
+
+impl std::ops::Add for Time {
+    type Output = Time;
+
+    fn add(self, rhs: Time) -> Self::Output {
+        Time::from(self.to_i64() + rhs.to_i64())
+    }
+}
+
+impl std::ops::Sub for Time {
+    type Output = Time;
+
+    fn sub(self, rhs: Time) -> Self::Output {
+        Time::from(self.to_i64() - rhs.to_i64())
+    }
+}
+
+impl std::ops::Neg for Time {
+    type Output = Time;
+
+    fn neg(self) -> Self::Output {
+        Time::from(-self.to_i64())
+    }
+}
+
+impl std::ops::Mul<i64> for Time {
+    type Output = Time;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Time::from(self.to_i64() * rhs)
+    }
+}
+
+impl std::ops::Mul<Time> for i64 {
+    type Output = Time;
+
+    fn mul(self, rhs: Time) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl std::ops::Div<i64> for Time {
+    type Output = Time;
+
+    fn div(self, rhs: i64) -> Self::Output {
+        Time::from(self.to_i64() / rhs)
+    }
+}
+
+impl std::ops::Div<Time> for Time {
+    type Output = i64;
+
+    fn div(self, rhs: Time) -> Self::Output {
+        self.to_i64() / rhs.to_i64()
+    }
+}
+
+impl std::ops::Rem<Time> for Time {
+    type Output = Time;
+
+    fn rem(self, rhs: Time) -> Self::Output {
+        Time::from(self.to_i64() % rhs.to_i64())
+    }
+}
+
+impl std::ops::AddAssign for Time {
+    fn add_assign(&mut self, rhs: Time) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::SubAssign for Time {
+    fn sub_assign(&mut self, rhs: Time) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::MulAssign<i64> for Time {
+    fn mul_assign(&mut self, rhs: i64) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::ops::DivAssign<i64> for Time {
+    fn div_assign(&mut self, rhs: i64) {
+        *self = *self / rhs;
+    }
+}
+
+impl std::iter::Sum for Time {
+    fn sum<I: Iterator<Item = Time>>(iter: I) -> Self {
+        iter.fold(Time::ZERO, |acc, time| acc + time)
+    }
+}
+
+impl std::str::FromStr for Time {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Time::parse(s)
+    }
+}
+
+impl TryFrom<Time> for Duration {
+    type Error = Error;
+
+    /// Convert a non-negative time, truncating to whole nanoseconds.
+    fn try_from(time: Time) -> Result<Self, Self::Error> {
+        let fs = u64::try_from(time.to_i64())
+            .map_err(|_| Error::from("negative time cannot be converted to a Duration"))?;
+        Ok(Duration::from_nanos(fs / NS_I64.unsigned_abs()))
+    }
+}
+
+impl TryFrom<Duration> for Time {
+    type Error = Error;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        i64::try_from(duration.as_nanos())
+            .ok()
+            .and_then(|nanos| nanos.checked_mul(NS_I64))
+            .map(Time::from)
+            .ok_or_else(|| Error::from("Duration is too long for a simulation time"))
+    }
+}
+
 impl std::fmt::Display for Time {
+    /// Formats in the largest unit that shows the time exactly, or with a
+    /// precision such as `{:.2}` in the largest unit not exceeding it.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(precision) = f.precision() {
+            return f.write_str(&self.format_in(TimeUnit::largest_within(*self), precision));
+        }
         let time = self.to_i64();
         if time.is_zero() {
             write!(f, "0")
@@ -136,11 +283,254 @@ impl std::fmt::Display for Time {
     }
 }
 impl Time {
+    /// Zero time.
+    pub const ZERO: Time = Time { high: 0, low: 0 };
+    /// The largest time, `TIME'HIGH`.
+    pub const MAX: Time = Time {
+        high: i32::MAX,
+        low: u32::MAX,
+    };
+    /// The smallest time, `TIME'LOW`.
+    pub const MIN: Time = Time {
+        high: i32::MIN,
+        low: 0,
+    };
+
     #[must_use]
     /// Convert this split representation into a single `i64` value.
     pub const fn to_i64(&self) -> i64 {
         (self.high as i64) << 32 | (self.low as i64)
     }
+
+    /// Parse a VHDL time literal such as `12.5 ns`, `-3 us` or `ps`.
+    ///
+    /// Both the VHDL unit names and those used by [`Display`](std::fmt::Display),
+    /// such as `µs` and `s`, are accepted, as is the bare `0` it prints for
+    /// [`Time::ZERO`].  Fractions finer than 1 fs are rounded to the nearest
+    /// femtosecond.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` is not a time literal or is out of
+    /// range.
+    pub fn parse(text: &str) -> Result<Time, Error> {
+        if text.trim() == "0" {
+            return Ok(Time::ZERO);
+        }
+        match Value::parse_vhdl(text, &TypeHint::new(Format::Time))? {
+            Value::Time(time) => Ok(time),
+            _ => Err("not a time literal".into()),
+        }
+    }
+
+    #[must_use]
+    /// Whether this time is zero.
+    pub const fn is_zero(&self) -> bool {
+        self.to_i64() == 0
+    }
+
+    #[must_use]
+    /// Whether this time is less than zero.
+    pub const fn is_negative(&self) -> bool {
+        self.to_i64() < 0
+    }
+
+    #[must_use]
+    /// Absolute value, saturating at [`Time::MAX`].
+    pub const fn abs(self) -> Time {
+        Time::from_i64(self.to_i64().saturating_abs())
+    }
+
+    #[must_use]
+    /// Add `rhs`, returning `None` on overflow.
+    pub fn checked_add(self, rhs: Time) -> Option<Time> {
+        self.to_i64().checked_add(rhs.to_i64()).map(Time::from)
+    }
+
+    #[must_use]
+    /// Subtract `rhs`, returning `None` on overflow.
+    pub fn checked_sub(self, rhs: Time) -> Option<Time> {
+        self.to_i64().checked_sub(rhs.to_i64()).map(Time::from)
+    }
+
+    #[must_use]
+    /// Multiply by `rhs`, returning `None` on overflow.
+    pub fn checked_mul(self, rhs: i64) -> Option<Time> {
+        self.to_i64().checked_mul(rhs).map(Time::from)
+    }
+
+    #[must_use]
+    /// Divide by `rhs`, returning `None` if `rhs` is zero or on overflow.
+    pub fn checked_div(self, rhs: i64) -> Option<Time> {
+        self.to_i64().checked_div(rhs).map(Time::from)
+    }
+
+    #[must_use]
+    /// Negate, returning `None` for [`Time::MIN`].
+    pub fn checked_neg(self) -> Option<Time> {
+        self.to_i64().checked_neg().map(Time::from)
+    }
+
+    #[must_use]
+    /// Add `rhs`, clamping to [`Time::MIN`] and [`Time::MAX`].
+    pub fn saturating_add(self, rhs: Time) -> Time {
+        Time::from(self.to_i64().saturating_add(rhs.to_i64()))
+    }
+
+    #[must_use]
+    /// Subtract `rhs`, clamping to [`Time::MIN`] and [`Time::MAX`].
+    pub fn saturating_sub(self, rhs: Time) -> Time {
+        Time::from(self.to_i64().saturating_sub(rhs.to_i64()))
+    }
+
+    #[must_use]
+    /// Multiply by `rhs`, clamping to [`Time::MIN`] and [`Time::MAX`].
+    pub fn saturating_mul(self, rhs: i64) -> Time {
+        Time::from(self.to_i64().saturating_mul(rhs))
+    }
+
+    #[must_use]
+    /// Round to the nearest multiple of `resolution`, with ties away from
+    /// zero.
+    ///
+    /// A zero or negative `resolution` leaves the time unchanged, and
+    /// results beyond the range of [`Time`] saturate.
+    pub fn round_to(self, resolution: Time) -> Time {
+        let time = i128::from(self.to_i64());
+        let step = i128::from(resolution.to_i64());
+        if step <= 0 {
+            return self;
+        }
+        let rounded = (2 * time + time.signum() * step) / (2 * step) * step;
+        Time::from(i64::try_from(rounded).unwrap_or(if time < 0 { i64::MIN } else { i64::MAX }))
+    }
+
+    #[must_use]
+    /// Round to the nearest multiple of
+    /// [`simulator_time_resolution`](crate::simulator_time_resolution).
+    pub fn round_to_resolution(self) -> Time {
+        self.round_to(simulator_time_resolution())
+    }
+
+    #[must_use]
+    /// This time as a possibly fractional number of `unit`.
+    pub fn as_unit(&self, unit: TimeUnit) -> f64 {
+        self.to_i64() as f64 / unit.time().to_i64() as f64
+    }
+
+    #[must_use]
+    /// Format as a number of `unit` with `precision` decimal places,
+    /// rounding half away from zero, e.g. `12.50 ns`.
+    pub fn format_in(&self, unit: TimeUnit, precision: usize) -> String {
+        let value = format_scaled(self.to_i64(), unit.time().to_i64(), precision);
+        format!("{value} {unit}")
+    }
+
+    const fn from_i64(value: i64) -> Time {
+        Time {
+            low: value as u32,
+            high: (value >> 32) as i32,
+        }
+    }
+}
+
+/// A unit of the VHDL `TIME` type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TimeUnit {
+    /// Femtoseconds, `fs`.
+    Fs,
+    /// Picoseconds, `ps`.
+    Ps,
+    /// Nanoseconds, `ns`.
+    Ns,
+    /// Microseconds, `us`.
+    Us,
+    /// Milliseconds, `ms`.
+    Ms,
+    /// Seconds, `sec`.
+    Sec,
+    /// Minutes, `min`.
+    Min,
+    /// Hours, `hr`.
+    Hr,
+}
+
+impl TimeUnit {
+    /// All units, smallest first.
+    pub const ALL: [TimeUnit; 8] = [
+        TimeUnit::Fs,
+        TimeUnit::Ps,
+        TimeUnit::Ns,
+        TimeUnit::Us,
+        TimeUnit::Ms,
+        TimeUnit::Sec,
+        TimeUnit::Min,
+        TimeUnit::Hr,
+    ];
+
+    #[must_use]
+    /// One of this unit.
+    pub const fn time(self) -> Time {
+        match self {
+            TimeUnit::Fs => FS,
+            TimeUnit::Ps => PS,
+            TimeUnit::Ns => NS,
+            TimeUnit::Us => US,
+            TimeUnit::Ms => MS,
+            TimeUnit::Sec => S,
+            TimeUnit::Min => MN,
+            TimeUnit::Hr => HR,
+        }
+    }
+
+    #[must_use]
+    /// Name of the unit in the `STD.STANDARD` declaration of `TIME`.
+    pub const fn vhdl_name(self) -> &'static str {
+        match self {
+            TimeUnit::Fs => "fs",
+            TimeUnit::Ps => "ps",
+            TimeUnit::Ns => "ns",
+            TimeUnit::Us => "us",
+            TimeUnit::Ms => "ms",
+            TimeUnit::Sec => "sec",
+            TimeUnit::Min => "min",
+            TimeUnit::Hr => "hr",
+        }
+    }
+
+    #[must_use]
+    /// Find a unit by its VHDL name or display symbol, ignoring case.
+    pub fn from_name(name: &str) -> Option<TimeUnit> {
+        TimeUnit::ALL
+            .into_iter()
+            .find(|unit| unit.vhdl_name().eq_ignore_ascii_case(name) || unit.to_string() == name)
+    }
+
+    /// The largest unit no bigger than `time`, or [`TimeUnit::Fs`].
+    fn largest_within(time: Time) -> TimeUnit {
+        let time = time.to_i64().unsigned_abs();
+        TimeUnit::ALL
+            .into_iter()
+            .rev()
+            .find(|unit| unit.time().to_i64().unsigned_abs() <= time)
+            .unwrap_or(TimeUnit::Fs)
+    }
+}
+
+impl std::fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            TimeUnit::Fs => "fs",
+            TimeUnit::Ps => "ps",
+            TimeUnit::Ns => "ns",
+            TimeUnit::Us => "µs",
+            TimeUnit::Ms => "ms",
+            TimeUnit::Sec => "s",
+            TimeUnit::Min => "min",
+            TimeUnit::Hr => "hr",
+        };
+        f.write_str(symbol)
+    }
 }
 
 #[must_use]
@@ -259,4 +649,106 @@ mod tests {
         assert_eq!(HR.to_string(), "1 hr");
         assert_eq!((MN * Time::from(90_i64)).to_string(), "90 min");
     }
+
+    #[test]
+    fn time_arithmetic_and_ordering() {
+        let a = Time::from(1_500_i64);
+        let b = PS;
+        assert_eq!(a + b, Time::from(2_500_i64));
+        assert_eq!(a - b * 2, Time::from(-500_i64));
+        assert_eq!(-a, Time::from(-1_500_i64));
+        assert_eq!(3 * b / 2, a);
+        assert_eq!(a / b, 1);
+        assert_eq!(a % b, Time::from(500_i64));
+        assert!(Time::from(-1_i64) < Time::ZERO);
+        assert!(Time::from(1_i64 << 32) > Time::from(u32::MAX));
+        assert_eq!([a, b].into_iter().sum::<Time>(), Time::from(2_500_i64));
+        assert_eq!([NS, PS, FS].into_iter().max(), Some(NS));
+
+        let mut t = NS;
+        t += PS;
+        t -= NS;
+        assert_eq!(t, PS);
+        assert_eq!(Time::MAX.to_i64(), i64::MAX);
+        assert_eq!(Time::MIN.to_i64(), i64::MIN);
+    }
+
+    #[test]
+    fn time_checked_and_saturating_ops() {
+        assert_eq!(Time::MAX.checked_add(FS), None);
+        assert_eq!(Time::MIN.checked_sub(FS), None);
+        assert_eq!(HR.checked_mul(i64::MAX), None);
+        assert_eq!(NS.checked_div(0), None);
+        assert_eq!(Time::MIN.checked_neg(), None);
+        assert_eq!(NS.checked_add(PS), Some(Time::from(1_001_000_i64)));
+        assert_eq!(Time::MAX.saturating_add(NS), Time::MAX);
+        assert_eq!(Time::MIN.saturating_sub(NS), Time::MIN);
+        assert_eq!(HR.saturating_mul(-i64::MAX), Time::MIN);
+        assert_eq!(Time::MIN.abs(), Time::MAX);
+    }
+
+    #[test]
+    fn time_parses_literals() {
+        assert_eq!(Time::parse("12.5 ns").unwrap(), Time::from(12_500_000_i64));
+        assert_eq!(
+            Time::parse("-3 us").unwrap(),
+            Time::from(-3_000_000_000_i64)
+        );
+        assert_eq!(Time::parse("2 sec").unwrap(), S * Time::from(2_i64));
+        assert_eq!("5 µs".parse::<Time>().unwrap(), US * Time::from(5_i64));
+        assert_eq!(Time::parse("ps").unwrap(), PS);
+        assert!(Time::parse("12.5").is_err());
+        assert!(Time::parse("1").is_err());
+        assert!(Time::parse("1 fortnight").is_err());
+        for time in [
+            Time::ZERO,
+            Time::from(123_i64),
+            NS,
+            MS * Time::from(7_i64),
+            HR,
+        ] {
+            assert_eq!(Time::parse(&time.to_string()).unwrap(), time);
+        }
+    }
+
+    #[test]
+    fn time_converts_to_and_from_duration() {
+        let time = Time::try_from(Duration::from_micros(15)).unwrap();
+        assert_eq!(time, US * Time::from(15_i64));
+        assert_eq!(
+            Duration::try_from(Time::from(2_500_999_i64)).unwrap(),
+            Duration::from_nanos(2)
+        );
+        assert!(Duration::try_from(-NS).is_err());
+        assert!(Time::try_from(Duration::from_secs(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn time_rounds_to_resolution() {
+        assert_eq!(Time::from(1_499_i64).round_to(PS), PS);
+        assert_eq!(Time::from(1_500_i64).round_to(PS), PS * Time::from(2_i64));
+        assert_eq!(
+            Time::from(-1_500_i64).round_to(PS),
+            -(PS * Time::from(2_i64))
+        );
+        assert_eq!(Time::from(-1_499_i64).round_to(PS), -PS);
+        assert_eq!(Time::from(7_i64).round_to(Time::ZERO), Time::from(7_i64));
+        assert_eq!(Time::MAX.round_to(HR), Time::MAX);
+    }
+
+    #[test]
+    fn time_formats_with_unit_and_precision() {
+        let time = Time::from(12_345_678_i64);
+        assert_eq!(time.format_in(TimeUnit::Ns, 2), "12.35 ns");
+        assert_eq!(time.format_in(TimeUnit::Us, 0), "0 µs");
+        assert_eq!(time.format_in(TimeUnit::Ps, 1), "12345.7 ps");
+        assert_eq!((-time).format_in(TimeUnit::Ns, 3), "-12.346 ns");
+        assert_eq!(NS.format_in(TimeUnit::Ns, 20), "1.00000000000000000000 ns");
+        assert_eq!(format!("{time:.1}"), "12.3 ns");
+        assert_eq!(format!("{:.2}", HR * Time::from(2_i64)), "2.00 hr");
+        assert_eq!(format!("{:.0}", Time::from(3_i64)), "3 fs");
+        assert!((time.as_unit(TimeUnit::Ns) - 12.345_678).abs() < 1e-9);
+        assert_eq!(TimeUnit::from_name("SEC"), Some(TimeUnit::Sec));
+        assert_eq!(TimeUnit::from_name("µs"), Some(TimeUnit::Us));
+    }
 }
@@ -341,6 +341,24 @@ impl From<PutValueMode> for vhpi_sys::vhpiPutValueModeT {
     }
 }
 
+/// Delay mechanism used by [`Handle::schedule_transaction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelayMode {
+    /// Inertial delay, pulses shorter than the delay are rejected.
+    Inertial,
+    /// Transport delay, all pulses are propagated.
+    Transport,
+}
+
+impl From<DelayMode> for vhpi_sys::vhpiDelayModeT {
+    fn from(mode: DelayMode) -> Self {
+        match mode {
+            DelayMode::Inertial => vhpi_sys::vhpiDelayModeT_vhpiInertial,
+            DelayMode::Transport => vhpi_sys::vhpiDelayModeT_vhpiTransport,
+        }
+    }
+}
+
 #[derive(Debug)]
 enum VectorBox {
     #[allow(dead_code)]
@@ -642,186 +660,7 @@ impl Handle {
     ///
     /// Returns an error when the simulator rejects the value write.
     pub fn put_value(&self, value: Value, mode: PutValueMode) -> Result<(), Error> {
-        // Create a holder for any allocated buffer
-        let mut buffer_holder: Option<VectorBox> = None;
-
-        let (format, val) = match value {
-            Value::Int(n) => (Format::Int, vhpi_sys::vhpiValueS__bindgen_ty_1 { intg: n }),
-            Value::Logic(n) => (
-                Format::Logic,
-                vhpi_sys::vhpiValueS__bindgen_ty_1 { enumv: n.into() },
-            ),
-            Value::Enum(n) => (
-                Format::Enum,
-                vhpi_sys::vhpiValueS__bindgen_ty_1 { enumv: n },
-            ),
-            Value::SmallEnum(n) => (
-                Format::SmallEnum,
-                vhpi_sys::vhpiValueS__bindgen_ty_1 { smallenumv: n },
-            ),
-            Value::BinStr(s) => {
-                let c_string = string_to_iso8859_1_cstring(s);
-                let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
-                (
-                    Format::BinStr,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
-                )
-            }
-            Value::OctStr(s) => {
-                let c_string = string_to_iso8859_1_cstring(s);
-                let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
-                (
-                    Format::OctStr,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
-                )
-            }
-            Value::HexStr(s) => {
-                let c_string = string_to_iso8859_1_cstring(s);
-                let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
-                (
-                    Format::HexStr,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
-                )
-            }
-            Value::DecStr(s) => {
-                let c_string = string_to_iso8859_1_cstring(s);
-                let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
-                (
-                    Format::DecStr,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
-                )
-            }
-            Value::Str(s) => {
-                let c_string = string_to_iso8859_1_cstring(s);
-                let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
-                (
-                    Format::Str,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
-                )
-            }
-            Value::LogicVec(vec) => {
-                let mut buffer: Vec<vhpi_sys::vhpiEnumT> =
-                    vec.iter().map(|&val| val.into()).collect();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::Enum(buffer));
-                (
-                    Format::LogicVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { enumvs: ptr },
-                )
-            }
-            Value::IntVec(vec) => {
-                let mut buffer: Vec<vhpi_sys::vhpiIntT> = vec.clone();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::Int(buffer));
-                (
-                    Format::IntVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { intgs: ptr },
-                )
-            }
-            Value::RealVec(vec) => {
-                let mut buffer: Vec<vhpi_sys::vhpiRealT> = vec.clone();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::Real(buffer));
-                (
-                    Format::RealVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { reals: ptr },
-                )
-            }
-            Value::Time(t) => (
-                Format::Time,
-                vhpi_sys::vhpiValueS__bindgen_ty_1 { time: t.into() },
-            ),
-            Value::TimeVec(vec) => {
-                let mut buffer: Vec<vhpi_sys::vhpiTimeT> =
-                    vec.iter().map(|val| val.clone().into()).collect();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::Time(buffer));
-                (
-                    Format::TimeVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { times: ptr },
-                )
-            }
-            Value::Real(n) => (Format::Real, vhpi_sys::vhpiValueS__bindgen_ty_1 { real: n }),
-            Value::Char(c) => (
-                Format::Char,
-                vhpi_sys::vhpiValueS__bindgen_ty_1 { ch: c as u8 },
-            ),
-            Value::SmallEnumVec(v) => {
-                let mut buffer: Vec<vhpi_sys::vhpiSmallEnumT> = v.clone();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::SmallEnum(buffer));
-                (
-                    Format::SmallEnumVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { smallenumvs: ptr },
-                )
-            }
-            Value::EnumVec(v) => {
-                let mut buffer: Vec<vhpi_sys::vhpiEnumT> = v.clone();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::Enum(buffer));
-                (
-                    Format::EnumVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { enumvs: ptr },
-                )
-            }
-            Value::LongInt(l) => (
-                Format::LongInt,
-                vhpi_sys::vhpiValueS__bindgen_ty_1 { longintg: l },
-            ),
-            Value::LongIntVec(vec) => {
-                let mut buffer: Vec<vhpi_sys::vhpiLongIntT> = vec.clone();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::LongInt(buffer));
-                (
-                    Format::LongIntVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { longintgs: ptr },
-                )
-            }
-            Value::SmallPhysical(s) => (
-                Format::SmallPhysical,
-                vhpi_sys::vhpiValueS__bindgen_ty_1 { smallphys: s },
-            ),
-            Value::SmallPhysicalVec(vec) => {
-                let mut buffer: Vec<vhpi_sys::vhpiSmallPhysT> = vec.clone();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::SmallPhys(buffer));
-                (
-                    Format::SmallPhysicalVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { smallphyss: ptr },
-                )
-            }
-            Value::Physical(p) => (
-                Format::Physical,
-                vhpi_sys::vhpiValueS__bindgen_ty_1 { phys: p.into() },
-            ),
-            Value::PhysicalVec(vec) => {
-                let mut buffer: Vec<vhpi_sys::vhpiPhysT> =
-                    vec.iter().map(|val| val.clone().into()).collect();
-                let ptr = buffer.as_mut_ptr();
-                buffer_holder = Some(VectorBox::Phys(buffer));
-                (
-                    Format::PhysicalVec,
-                    vhpi_sys::vhpiValueS__bindgen_ty_1 { physs: ptr },
-                )
-            }
-            Value::Unknown => return Err("Cannot put unknown value".into()),
-        };
-
-        let mut val_struct = vhpi_sys::vhpiValueT {
-            format: format.into(),
-            bufSize: 0,
-            numElems: 0,
-            unit: vhpi_sys::vhpiPhysS { high: 0, low: 0 },
-            value: val,
-        };
-
-        if let Some(buffer) = buffer_holder.as_ref() {
-            val_struct.bufSize = buffer.byte_len();
-            val_struct.numElems = buffer
-                .len()
-                .try_into()
-                .expect("vector element count does not fit into vhpi element count type");
-        }
+        let (mut val_struct, buffer_holder) = raw_value(value)?;
 
         let rc =
             unsafe { vhpi_sys::vhpi_put_value(self.as_raw(), &raw mut val_struct, mode.into()) };
@@ -835,6 +674,234 @@ impl Handle {
             Err(crate::check_error().unwrap_or_else(|| "Unknown error in vhpi_put_value".into()))
         }
     }
+
+    /// Schedule a transaction on this driver or signal after `delay`.
+    ///
+    /// This is the VHPI equivalent of a signal assignment statement: the new
+    /// value becomes effective in a later delta or time step rather than
+    /// immediately as with [`Handle::put_value`].  A zero `delay` schedules
+    /// the transaction for the next delta cycle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the simulator rejects the transaction.
+    pub fn schedule_transaction(
+        &self,
+        value: Value,
+        delay: Time,
+        mode: DelayMode,
+    ) -> Result<(), Error> {
+        let (mut val_struct, buffer_holder) = raw_value(value)?;
+        let mut delay: vhpi_sys::vhpiTimeT = delay.into();
+
+        let rc = unsafe {
+            vhpi_sys::vhpi_schedule_transaction(
+                self.as_raw(),
+                &raw mut val_struct,
+                1,
+                &raw mut delay,
+                mode.into(),
+                std::ptr::null_mut(),
+            )
+        };
+
+        // Keep buffer_holder alive until after vhpi_schedule_transaction
+        let _ = &buffer_holder;
+
+        if rc.is_zero() {
+            Ok(())
+        } else {
+            Err(crate::check_error()
+                .unwrap_or_else(|| "Unknown error in vhpi_schedule_transaction".into()))
+        }
+    }
+}
+
+/// Convert `value` into a raw VHPI value structure.
+///
+/// Vector data is stored in the returned [`VectorBox`] which must outlive any
+/// use of the structure.
+fn raw_value(value: Value) -> Result<(vhpi_sys::vhpiValueT, Option<VectorBox>), Error> {
+    // Create a holder for any allocated buffer
+    let mut buffer_holder: Option<VectorBox> = None;
+
+    let (format, val) = match value {
+        Value::Int(n) => (Format::Int, vhpi_sys::vhpiValueS__bindgen_ty_1 { intg: n }),
+        Value::Logic(n) => (
+            Format::Logic,
+            vhpi_sys::vhpiValueS__bindgen_ty_1 { enumv: n.into() },
+        ),
+        Value::Enum(n) => (
+            Format::Enum,
+            vhpi_sys::vhpiValueS__bindgen_ty_1 { enumv: n },
+        ),
+        Value::SmallEnum(n) => (
+            Format::SmallEnum,
+            vhpi_sys::vhpiValueS__bindgen_ty_1 { smallenumv: n },
+        ),
+        Value::BinStr(s) => {
+            let c_string = string_to_iso8859_1_cstring(s);
+            let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
+            (
+                Format::BinStr,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
+            )
+        }
+        Value::OctStr(s) => {
+            let c_string = string_to_iso8859_1_cstring(s);
+            let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
+            (
+                Format::OctStr,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
+            )
+        }
+        Value::HexStr(s) => {
+            let c_string = string_to_iso8859_1_cstring(s);
+            let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
+            (
+                Format::HexStr,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
+            )
+        }
+        Value::DecStr(s) => {
+            let c_string = string_to_iso8859_1_cstring(s);
+            let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
+            (
+                Format::DecStr,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
+            )
+        }
+        Value::Str(s) => {
+            let c_string = string_to_iso8859_1_cstring(s);
+            let ptr = c_string.into_raw().cast::<vhpi_sys::vhpiCharT>();
+            (
+                Format::Str,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { str_: ptr },
+            )
+        }
+        Value::LogicVec(vec) => {
+            let mut buffer: Vec<vhpi_sys::vhpiEnumT> = vec.iter().map(|&val| val.into()).collect();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::Enum(buffer));
+            (
+                Format::LogicVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { enumvs: ptr },
+            )
+        }
+        Value::IntVec(vec) => {
+            let mut buffer: Vec<vhpi_sys::vhpiIntT> = vec.clone();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::Int(buffer));
+            (
+                Format::IntVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { intgs: ptr },
+            )
+        }
+        Value::RealVec(vec) => {
+            let mut buffer: Vec<vhpi_sys::vhpiRealT> = vec.clone();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::Real(buffer));
+            (
+                Format::RealVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { reals: ptr },
+            )
+        }
+        Value::Time(t) => (
+            Format::Time,
+            vhpi_sys::vhpiValueS__bindgen_ty_1 { time: t.into() },
+        ),
+        Value::TimeVec(vec) => {
+            let mut buffer: Vec<vhpi_sys::vhpiTimeT> = vec.iter().map(|&val| val.into()).collect();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::Time(buffer));
+            (
+                Format::TimeVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { times: ptr },
+            )
+        }
+        Value::Real(n) => (Format::Real, vhpi_sys::vhpiValueS__bindgen_ty_1 { real: n }),
+        Value::Char(c) => (
+            Format::Char,
+            vhpi_sys::vhpiValueS__bindgen_ty_1 { ch: c as u8 },
+        ),
+        Value::SmallEnumVec(v) => {
+            let mut buffer: Vec<vhpi_sys::vhpiSmallEnumT> = v.clone();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::SmallEnum(buffer));
+            (
+                Format::SmallEnumVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { smallenumvs: ptr },
+            )
+        }
+        Value::EnumVec(v) => {
+            let mut buffer: Vec<vhpi_sys::vhpiEnumT> = v.clone();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::Enum(buffer));
+            (
+                Format::EnumVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { enumvs: ptr },
+            )
+        }
+        Value::LongInt(l) => (
+            Format::LongInt,
+            vhpi_sys::vhpiValueS__bindgen_ty_1 { longintg: l },
+        ),
+        Value::LongIntVec(vec) => {
+            let mut buffer: Vec<vhpi_sys::vhpiLongIntT> = vec.clone();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::LongInt(buffer));
+            (
+                Format::LongIntVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { longintgs: ptr },
+            )
+        }
+        Value::SmallPhysical(s) => (
+            Format::SmallPhysical,
+            vhpi_sys::vhpiValueS__bindgen_ty_1 { smallphys: s },
+        ),
+        Value::SmallPhysicalVec(vec) => {
+            let mut buffer: Vec<vhpi_sys::vhpiSmallPhysT> = vec.clone();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::SmallPhys(buffer));
+            (
+                Format::SmallPhysicalVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { smallphyss: ptr },
+            )
+        }
+        Value::Physical(p) => (
+            Format::Physical,
+            vhpi_sys::vhpiValueS__bindgen_ty_1 { phys: p.into() },
+        ),
+        Value::PhysicalVec(vec) => {
+            let mut buffer: Vec<vhpi_sys::vhpiPhysT> =
+                vec.iter().map(|val| val.clone().into()).collect();
+            let ptr = buffer.as_mut_ptr();
+            buffer_holder = Some(VectorBox::Phys(buffer));
+            (
+                Format::PhysicalVec,
+                vhpi_sys::vhpiValueS__bindgen_ty_1 { physs: ptr },
+            )
+        }
+        Value::Unknown => return Err("Cannot put unknown value".into()),
+    };
+
+    let mut val_struct = vhpi_sys::vhpiValueT {
+        format: format.into(),
+        bufSize: 0,
+        numElems: 0,
+        unit: vhpi_sys::vhpiPhysS { high: 0, low: 0 },
+        value: val,
+    };
+
+    if let Some(buffer) = buffer_holder.as_ref() {
+        val_struct.bufSize = buffer.byte_len();
+        val_struct.numElems = buffer
+            .len()
+            .try_into()
+            .expect("vector element count does not fit into vhpi element count type");
+    }
+
+    Ok((val_struct, buffer_holder))
 }
 
 /// Convert a string to a [`Value::LogicVec`] by mapping each character to a [`LogicVal`] using the character's byte value.